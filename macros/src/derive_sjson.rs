@@ -0,0 +1,552 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Attribute, Data, DeriveInput, Fields, LitStr, Member};
+
+/// Options from `#[sjson(...)]` on the struct or enum itself.
+#[derive(Default)]
+struct ContainerOptions {
+    ident: Option<LitStr>,
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+    rename_all: Option<RenameRule>,
+}
+
+/// Options from `#[sjson(...)]` on a field or a variant.
+#[derive(Default)]
+struct ItemOptions {
+    rename: Option<String>,
+    skip: bool,
+    flatten: bool,
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lowercase,
+    Uppercase,
+    SnakeCase,
+    CamelCase,
+    KebabCase,
+}
+
+impl RenameRule {
+    fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "lowercase" => Ok(RenameRule::Lowercase),
+            "UPPERCASE" => Ok(RenameRule::Uppercase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            _ => Err(syn::Error::new(lit.span(), "unknown rename rule, expected one of `lowercase`, `UPPERCASE`, `snake_case`, `camelCase` or `kebab-case`")),
+        }
+    }
+
+    /// Apply the rule to a `PascalCase` variant or a `snake_case` field name.
+    fn apply(self, name: &str) -> String {
+        let mut words: Vec<String> = vec![];
+
+        for part in name.split('_').filter(|part| !part.is_empty()) {
+            let mut word = String::new();
+
+            for c in part.chars() {
+                if c.is_uppercase() && !word.is_empty() {
+                    words.push(word);
+                    word = String::new();
+                }
+                word.extend(c.to_lowercase());
+            }
+
+            words.push(word);
+        }
+
+        match self {
+            RenameRule::Lowercase => words.concat(),
+            RenameRule::Uppercase => words.concat().to_uppercase(),
+            RenameRule::SnakeCase => words.join("_"),
+            RenameRule::KebabCase => words.join("-"),
+            RenameRule::CamelCase => words.iter().enumerate().map(|(i, word)| {
+                let mut chars = word.chars();
+
+                match chars.next() {
+                    Some(first) if i > 0 => first.to_uppercase().chain(chars).collect(),
+                    _ => word.clone(),
+                }
+            }).collect(),
+        }
+    }
+}
+
+fn container_options(attrs: &[Attribute]) -> syn::Result<ContainerOptions> {
+    let mut options = ContainerOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("sjson")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ident") {
+                options.ident = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("tag") {
+                options.tag = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("content") {
+                options.content = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("untagged") {
+                options.untagged = true;
+            } else if meta.path.is_ident("rename_all") {
+                options.rename_all = Some(RenameRule::from_lit(&meta.value()?.parse()?)?);
+            } else {
+                return Err(meta.error("unknown sjson attribute, expected `ident`, `tag`, `content`, `untagged` or `rename_all`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn item_options(attrs: &[Attribute]) -> syn::Result<ItemOptions> {
+    let mut options = ItemOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("sjson")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+            } else {
+                return Err(meta.error("unknown sjson attribute, expected `rename`, `skip` or `flatten`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+struct FieldInfo {
+    member: Member,
+    binding: Ident,
+    key: String,
+    options: ItemOptions,
+}
+
+fn field_infos(fields: &Fields, rename_all: Option<RenameRule>) -> syn::Result<Vec<FieldInfo>> {
+    fields.iter().enumerate().map(|(i, field)| {
+        let options = item_options(&field.attrs)?;
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        };
+        let name = field.ident.as_ref().map(|ident| ident.to_string().trim_start_matches("r#").to_string()).unwrap_or_default();
+        let key = options.rename.clone().unwrap_or_else(|| rename_all.map_or(name.clone(), |rule| rule.apply(&name)));
+
+        if options.flatten && field.ident.is_none() {
+            return Err(syn::Error::new(field.span(), "only named fields can be flattened"));
+        }
+
+        Ok(FieldInfo { member, binding: format_ident!("field_{}", i, span = Span::mixed_site()), key, options })
+    }).collect()
+}
+
+/// A pattern binding every field which isn't skipped.
+fn destructure(path: TokenStream, fields: &Fields, infos: &[FieldInfo]) -> TokenStream {
+    let kept = infos.iter().filter(|info| !info.options.skip);
+
+    match fields {
+        Fields::Named(_) => {
+            let members = kept.clone().map(|info| &info.member);
+            let bindings = kept.map(|info| &info.binding);
+
+            quote! { #path { #(#members: #bindings,)* .. } }
+        }
+        Fields::Unnamed(_) => {
+            let bindings = infos.iter().map(|info| {
+                let binding = &info.binding;
+
+                if info.options.skip { quote! { _ } } else { quote! { #binding } }
+            });
+
+            quote! { #path ( #(#bindings),* ) }
+        }
+        Fields::Unit => path,
+    }
+}
+
+/// Merge `value` into `object`. Only objects, and `null` for nothing, can be merged; anything else panics with `context`
+/// rather than being dropped, as the result couldn't be read back.
+fn extend_object(object: &Ident, value: TokenStream, context: String) -> TokenStream {
+    quote! {
+        match #value {
+            eo::sjson::SJsonValue::Object(content) => #object.extend(content),
+            eo::sjson::SJsonValue::Null => {}
+            other => ::std::panic!("{}, found {}.", #context, other.type_name()),
+        }
+    }
+}
+
+/// The SJsonValue built from the bound fields. `owner` names the struct or variant in runtime errors.
+fn fields_value(fields: &Fields, infos: &[FieldInfo], owner: &str) -> TokenStream {
+    let kept: Vec<&FieldInfo> = infos.iter().filter(|info| !info.options.skip).collect();
+
+    match fields {
+        Fields::Named(_) => {
+            let object = Ident::new("object", Span::mixed_site());
+            let inserts = kept.iter().map(|FieldInfo { binding, key, member, options }| {
+                if options.flatten {
+                    let context = format!("The flattened field `{owner}.{}` must be an object", member.to_token_stream());
+
+                    extend_object(&object, quote! { eo::sjson::ToSJson::sjson(#binding) }, context)
+                } else {
+                    quote! { #object.insert(#key.to_string(), eo::sjson::ToSJson::sjson(#binding)); }
+                }
+            });
+
+            quote! { {
+                let mut #object = eo::sjson::SJsonObject::new();
+                #(#inserts)*
+                eo::sjson::SJsonValue::Object(#object)
+            } }
+        }
+        Fields::Unnamed(_) if kept.len() == 1 => {
+            let binding = &kept[0].binding;
+
+            quote! { eo::sjson::ToSJson::sjson(#binding) }
+        }
+        Fields::Unnamed(_) => {
+            let bindings = kept.iter().map(|info| &info.binding);
+
+            quote! { eo::sjson::SJsonValue::Array(::std::vec![#(eo::sjson::ToSJson::sjson(#bindings)),*]) }
+        }
+        Fields::Unit => quote! { eo::sjson::SJsonValue::Null },
+    }
+}
+
+pub fn derive_to_sjson(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = container_options(&input.attrs)?;
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = field_infos(&data.fields, options.rename_all)?;
+            let pattern = destructure(quote! { Self }, &data.fields, &infos);
+            let value = fields_value(&data.fields, &infos, &name.to_string());
+
+            quote! {
+                let #pattern = self;
+                #value
+            }
+        }
+        Data::Enum(data) => {
+            if options.content.is_some() && options.tag.is_none() {
+                return Err(syn::Error::new(name.span(), "`content` requires `tag` to be set as well"));
+            }
+
+            let arms = data.variants.iter().map(|variant| {
+                let variant_options = item_options(&variant.attrs)?;
+                let variant_name = &variant.ident;
+                let tag_value = variant_options.rename.clone()
+                    .unwrap_or_else(|| options.rename_all.map_or(variant_name.to_string(), |rule| rule.apply(&variant_name.to_string())));
+
+                let infos = field_infos(&variant.fields, None)?;
+                let pattern = destructure(quote! { Self::#variant_name }, &variant.fields, &infos);
+                let value = fields_value(&variant.fields, &infos, &format!("{name}::{variant_name}"));
+                let unit = matches!(variant.fields, Fields::Unit);
+                let object = Ident::new("object", Span::mixed_site());
+
+                let value = match (&options.tag, &options.content) {
+                    _ if options.untagged => value,
+                    (Some(tag), Some(content)) => {
+                        let content = (!unit).then(|| quote! { #object.insert(#content.to_string(), #value); });
+
+                        quote! { {
+                            let mut #object = eo::sjson::SJsonObject::new();
+                            #object.insert(#tag.to_string(), eo::sjson::SJsonValue::String(#tag_value.to_string()));
+                            #content
+                            eo::sjson::SJsonValue::Object(#object)
+                        } }
+                    }
+                    (Some(tag), None) => {
+                        if matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() != 1) {
+                            return Err(syn::Error::new(variant.span(), "internally tagged enums only support unit, newtype and struct variants"));
+                        }
+
+                        let context = format!("The internally tagged variant `{name}::{variant_name}` must contain an object");
+                        let content = (!unit).then(|| extend_object(&object, value, context));
+
+                        quote! { {
+                            let mut #object = eo::sjson::SJsonObject::new();
+                            #object.insert(#tag.to_string(), eo::sjson::SJsonValue::String(#tag_value.to_string()));
+                            #content
+                            eo::sjson::SJsonValue::Object(#object)
+                        } }
+                    }
+                    (None, _) if unit => quote! { eo::sjson::SJsonValue::String(#tag_value.to_string()) },
+                    (None, _) => quote! {
+                        eo::sjson::SJsonValue::Object(eo::sjson::SJsonObject::from([(#tag_value.to_string(), #value)]))
+                    },
+                };
+
+                Ok(quote! { #pattern => #value, })
+            }).collect::<syn::Result<Vec<TokenStream>>>()?;
+
+            if arms.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(name.span(), "ToSJson can't be derived for unions")),
+    };
+
+    let mut generics = input.generics.clone();
+    let params: Vec<Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote! { #param: eo::sjson::ToSJson });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics eo::sjson::ToSJson for #name #ty_generics #where_clause {
+            fn sjson(&self) -> eo::sjson::SJsonValue {
+                #body
+            }
+        }
+    })
+}
+
+pub fn derive_has_sjson_ident(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = container_options(&input.attrs)?;
+    let name = &input.ident;
+
+    let Some(ident) = options.ident else {
+        return Err(syn::Error::new(name.span(), "deriving HasSJsonIdent requires `#[sjson(ident = \"namespace:name\")]`"));
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics eo::sjson::HasSJsonIdent for #name #ty_generics #where_clause {
+            fn sjson_ident(&self) -> String {
+                #ident.to_string()
+            }
+        }
+    })
+}
+
+/// An expression converting `value` into `path` built from the given fields, evaluating to `Result<Self, FromSJsonError>`.
+fn fields_from_value(path: TokenStream, fields: &Fields, infos: &[FieldInfo], value: TokenStream) -> TokenStream {
+    let input = Ident::new("value", Span::mixed_site());
+    let kept = infos.iter().filter(|info| !info.options.skip).count();
+
+    let body = match fields {
+        Fields::Named(_) => {
+            let object = Ident::new("object", Span::mixed_site());
+            let fields = infos.iter().map(|FieldInfo { member, key, options, .. }| {
+                if options.skip {
+                    quote! { #member: ::std::default::Default::default() }
+                } else if options.flatten {
+                    quote! { #member: eo::sjson::FromSJson::from_sjson(#input)? }
+                } else {
+                    quote! { #member: eo::sjson::from_sjson_field(#object, #key)? }
+                }
+            });
+
+            quote! {
+                let #object = match #input {
+                    eo::sjson::SJsonValue::Object(#object) => #object,
+                    other => return Err(eo::sjson::FromSJsonError::expected("object", other)),
+                };
+
+                Ok(#path { #(#fields),* })
+            }
+        }
+        Fields::Unnamed(_) if kept == 1 => {
+            let fields = infos.iter().map(|info| {
+                if info.options.skip {
+                    quote! { ::std::default::Default::default() }
+                } else {
+                    quote! { eo::sjson::FromSJson::from_sjson(#input)? }
+                }
+            });
+
+            quote! { Ok(#path(#(#fields),*)) }
+        }
+        Fields::Unnamed(_) => {
+            let array = Ident::new("array", Span::mixed_site());
+            let mut index = 0usize;
+            let fields = infos.iter().map(|info| {
+                if info.options.skip {
+                    quote! { ::std::default::Default::default() }
+                } else {
+                    index += 1;
+                    let i = index - 1;
+
+                    quote! { eo::sjson::from_sjson_index(#array, #i)? }
+                }
+            }).collect::<Vec<TokenStream>>();
+
+            quote! {
+                let #array = match #input {
+                    eo::sjson::SJsonValue::Array(#array) => #array,
+                    other => return Err(eo::sjson::FromSJsonError::expected("array", other)),
+                };
+
+                if #array.len() != #kept {
+                    return Err(eo::sjson::FromSJsonError::new(format!("expected an array of {} elements, found {}", #kept, #array.len())));
+                }
+
+                Ok(#path(#(#fields),*))
+            }
+        }
+        Fields::Unit => quote! {
+            match #input {
+                eo::sjson::SJsonValue::Null => Ok(#path),
+                other => Err(eo::sjson::FromSJsonError::expected("null", other)),
+            }
+        },
+    };
+
+    quote! {
+        (|#input: &eo::sjson::SJsonValue| -> ::std::result::Result<Self, eo::sjson::FromSJsonError> {
+            #body
+        })(#value)
+    }
+}
+
+pub fn derive_from_sjson(input: DeriveInput) -> syn::Result<TokenStream> {
+    let options = container_options(&input.attrs)?;
+    let name = &input.ident;
+    let value = Ident::new("value", Span::mixed_site());
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = field_infos(&data.fields, options.rename_all)?;
+
+            fields_from_value(quote! { Self }, &data.fields, &infos, quote! { #value })
+        }
+        Data::Enum(data) => {
+            if options.content.is_some() && options.tag.is_none() {
+                return Err(syn::Error::new(name.span(), "`content` requires `tag` to be set as well"));
+            }
+
+            let mut variants = vec![];
+
+            for variant in &data.variants {
+                let variant_options = item_options(&variant.attrs)?;
+                let variant_name = &variant.ident;
+                let tag_value = variant_options.rename.clone()
+                    .unwrap_or_else(|| options.rename_all.map_or(variant_name.to_string(), |rule| rule.apply(&variant_name.to_string())));
+                let infos = field_infos(&variant.fields, None)?;
+
+                if options.tag.is_some() && options.content.is_none() && matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() != 1) {
+                    return Err(syn::Error::new(variant.span(), "internally tagged enums only support unit, newtype and struct variants"));
+                }
+
+                variants.push((tag_value, quote! { Self::#variant_name }, &variant.fields, infos));
+            }
+
+            let expected = variants.iter().map(|(tag, ..)| format!("`{tag}`")).collect::<Vec<String>>().join(", ");
+            let unknown = quote! {
+                other => Err(eo::sjson::FromSJsonError::new(format!("unknown variant `{}`, expected one of {}", other, #expected)))
+            };
+
+            if options.untagged {
+                let attempts = variants.iter().map(|(_, path, fields, infos)| {
+                    let attempt = fields_from_value(path.clone(), fields, infos, quote! { #value });
+
+                    quote! {
+                        if let Ok(parsed) = #attempt {
+                            return Ok(parsed);
+                        }
+                    }
+                });
+                let message = format!("the value did not match any variant of `{name}`");
+
+                quote! { {
+                    #(#attempts)*
+                    Err(eo::sjson::FromSJsonError::new(#message))
+                } }
+            } else if let Some(tag) = &options.tag {
+                let object = Ident::new("object", Span::mixed_site());
+                let arms = variants.iter().map(|(tag_value, path, fields, infos)| {
+                    let construct = match (&options.content, fields) {
+                        (_, Fields::Unit) => quote! { Ok(#path) },
+                        (Some(content), _) => {
+                            let content_value = quote! { #object.get(#content).unwrap_or(&eo::sjson::SJsonValue::Null) };
+                            let construct = fields_from_value(path.clone(), fields, infos, content_value);
+
+                            quote! { #construct.map_err(|e| e.at_key(#content)) }
+                        }
+                        (None, _) => fields_from_value(path.clone(), fields, infos, quote! { #value }),
+                    };
+
+                    quote! { #tag_value => #construct, }
+                });
+
+                quote! {
+                    let #object = match #value {
+                        eo::sjson::SJsonValue::Object(#object) => #object,
+                        other => return Err(eo::sjson::FromSJsonError::expected("object", other)),
+                    };
+                    let tag: String = eo::sjson::from_sjson_field(#object, #tag)?;
+
+                    match tag.as_str() {
+                        #(#arms)*
+                        #unknown.map_err(|e| e.at_key(#tag)),
+                    }
+                }
+            } else {
+                let unit_arms = variants.iter().filter(|(_, _, fields, _)| matches!(fields, Fields::Unit))
+                    .map(|(tag_value, path, ..)| quote! { #tag_value => Ok(#path), });
+                let object_arms = variants.iter().filter(|(_, _, fields, _)| !matches!(fields, Fields::Unit))
+                    .map(|(tag_value, path, fields, infos)| {
+                        let construct = fields_from_value(path.clone(), fields, infos, quote! { content });
+
+                        quote! { #tag_value => #construct.map_err(|e| e.at_key(#tag_value)), }
+                    });
+
+                quote! {
+                    match #value {
+                        eo::sjson::SJsonValue::String(tag) => match tag.as_str() {
+                            #(#unit_arms)*
+                            #unknown,
+                        },
+                        eo::sjson::SJsonValue::Object(object) if object.len() == 1 => {
+                            let (tag, content) = object.first().unwrap();
+
+                            match tag.as_str() {
+                                #(#object_arms)*
+                                #unknown,
+                            }
+                        }
+                        other => Err(eo::sjson::FromSJsonError::expected("a variant name or a single-key object", other)),
+                    }
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(name.span(), "FromSJson can't be derived for unions")),
+    };
+
+    let mut generics = input.generics.clone();
+    let params: Vec<Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote! { #param: eo::sjson::FromSJson });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics eo::sjson::FromSJson for #name #ty_generics #where_clause {
+            fn from_sjson(#value: &eo::sjson::SJsonValue) -> ::std::result::Result<Self, eo::sjson::FromSJsonError> {
+                #body
+            }
+        }
+    })
+}
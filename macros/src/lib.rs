@@ -1,17 +1,23 @@
 mod notifier_macros;
+mod derive_sjson;
 mod event_macro;
 mod sjson;
+mod sjson_schema;
+mod suggest;
+mod state_machine_macro;
 
 use proc_macro2::TokenTree;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{parse_macro_input, Expr, Token};
+use syn::{parse_macro_input, DeriveInput, Expr, Token};
 use syn::__private::TokenStream2;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
+use crate::derive_sjson::{derive_from_sjson, derive_has_sjson_ident, derive_to_sjson};
 use crate::event_macro::{EventInitMacro, EventMacro};
 use crate::notifier_macros::{NotifierCreation, ReactiveValueCreation};
 use crate::sjson::{SJsonMacro, SimplifiedSJsonMacro};
+use crate::state_machine_macro::StateMachineMacro;
 
 /// Helper macro to create [Notifier]s in a cleaner way.
 /// Examples:
@@ -93,6 +99,23 @@ pub fn event_init(token_stream: proc_macro::TokenStream) -> proc_macro::TokenStr
     quote! { #p }.into()
 }
 
+/// A macro for declaring a [StateMachine] together with its transition table.
+/// Every transition is written as `from => to on event`, optionally followed by `if guard`.
+///```rust
+///use macros::state_machine;
+///
+///state_machine! {
+///    door: DoorState, DoorEvent = DoorState::Closed;
+///    DoorState::Closed => DoorState::Opened on DoorEvent::Open;
+///    DoorState::Opened => DoorState::Closed on DoorEvent::Close if |_, _| true;
+///}
+///```
+#[proc_macro]
+pub fn state_machine(token_stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let p = parse_macro_input!(token_stream as StateMachineMacro);
+
+    quote! { #p }.into()
+}
 
 /// Macro for mimicking the infix functions from Kotlin
 /// ```rust
@@ -113,15 +136,28 @@ pub fn infix(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
 /// Macro for writing sJSON structures.
 /// Read more about sJSON on the eo wiki.
+/// Objects and arrays can contain `if $cond { ... } else { ... }`, `for $pattern in $iter { ... }` and `..$spread` entries.
+/// Spread values implement `SpreadObject` or `SpreadArray`, so spreading e.g. a number into an object doesn't compile.
+/// An `SJsonValue` is only checked at runtime: `null` spreads nothing, and a value of the wrong type panics.
+/// Keys which aren't identifiers can be written as strings (`"1.20.0" = ...`) or computed at runtime (`[$key] = ...`).
+/// `: expr` includes a value implementing `HasSJsonIdent` and `ToSJson` under its own identifier, in any object.
+/// A key written twice keeps its first position and takes its last value, whether it came from a key, an inclusion or a spread.
+/// `sjson!(schema = "item.schema.json", { ... })` checks the literal keys and values against a JSON Schema file,
+/// relative to the current file, and reports unknown keys and mistyped values as compile errors.
 /// ```rust
 /// use macros::{sjson, sjson_value};
 ///
 /// let v = sjson_value!(12.3);
+/// let names = ["a", "b"];
 ///
 /// let x = sjson! {
 ///     object {
 ///         value = "hi",
 ///         variable = $v,
+///         computed = $(2 * 21),
+///         missing = null,
+///         if $(names.len() > 1) { plural = true },
+///         names [for $name in $names { $name }],
 ///         nested_array [
 ///             {
 ///                 value = "sJSON stuff"
@@ -132,7 +168,14 @@ pub fn infix(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// ```
 #[proc_macro]
 pub fn sjson(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let p = parse_macro_input!(tks as SJsonMacro);
+    // Schema checks can report several errors, which have to be statements of a block to be valid in expression position.
+    let p = match syn::parse::<SJsonMacro>(tks) {
+        Ok(p) => p,
+        Err(e) => {
+            let errors = e.to_compile_error();
+            return quote! { { #errors } }.into();
+        }
+    };
 
     quote! {
         {
@@ -154,6 +197,67 @@ pub fn sjson_value(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
     quote! { #p }.into()
 }
 
+/// Derive `ToSJson` for a struct or an enum.
+/// Fields and variants can be configured with `#[sjson(rename = "minecraft:damage")]`, `#[sjson(skip)]` and `#[sjson(flatten)]`.
+/// Flattened fields and the content of internally tagged newtype variants must serialize to an object or `null`;
+/// anything else panics, as it couldn't be read back.
+/// Containers accept `#[sjson(rename_all = "snake_case")]`, and enums are externally tagged unless they use
+/// `#[sjson(tag = "type")]`, `#[sjson(tag = "type", content = "value")]` or `#[sjson(untagged)]`.
+/// ```rust
+/// use eo::sjson::ToSJson;
+///
+/// #[derive(ToSJson)]
+/// struct Damage {
+///     #[sjson(rename = "value")]
+///     amount: i32,
+///     #[sjson(skip)]
+///     cached: bool,
+/// }
+/// ```
+#[proc_macro_derive(ToSJson, attributes(sjson))]
+pub fn to_sjson(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(tks as DeriveInput);
+
+    derive_to_sjson(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derive `FromSJson` for a struct or an enum. Accepts the same `#[sjson(...)]` attributes as [ToSJson](macro@ToSJson),
+/// so a value converted with one can be read back with the other. Skipped fields are filled with `Default::default()`.
+/// ```rust
+/// use eo::sjson::{FromSJson, SJsonValue};
+///
+/// #[derive(FromSJson)]
+/// struct Damage {
+///     #[sjson(rename = "value")]
+///     amount: i32,
+/// }
+///
+/// let damage = Damage::from_sjson(&SJsonValue::parse(r#"{ "value": 7 }"#).unwrap()).unwrap();
+/// ```
+#[proc_macro_derive(FromSJson, attributes(sjson))]
+pub fn from_sjson(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(tks as DeriveInput);
+
+    derive_from_sjson(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derive `HasSJsonIdent`, taking the ident from `#[sjson(ident = "minecraft:icon")]`.
+/// ```rust
+/// use eo::sjson::HasSJsonIdent;
+///
+/// #[derive(HasSJsonIdent)]
+/// #[sjson(ident = "minecraft:icon")]
+/// struct Icon {
+///     texture: String,
+/// }
+/// ```
+#[proc_macro_derive(HasSJsonIdent, attributes(sjson))]
+pub fn has_sjson_ident(tks: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(tks as DeriveInput);
+
+    derive_has_sjson_ident(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
 struct InfixStatement {
     object: Ident,
     method: Ident,
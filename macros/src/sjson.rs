@@ -1,40 +1,445 @@
-use proc_macro2::{Delimiter, Ident, TokenStream};
-use quote::{quote, ToTokens, TokenStreamExt};
+use proc_macro2::{Delimiter, Ident, Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens, TokenStreamExt};
+use syn::buffer::Cursor;
 use syn::parse::{Parse, ParseStream};
-use syn::punctuated::Punctuated;
-use syn::{braced, bracketed, Expr, Token};
+use syn::spanned::Spanned;
+use syn::{braced, bracketed, parenthesized, Expr, ExprField, ExprPath, Member, Pat, Path, Token};
 use syn::token::Token;
+use crate::sjson_schema::{Literal, Schema};
 
 pub struct SJsonMacro {
-    jsons: Vec<SJsonElement>
+    jsons: Vec<Entry<SJsonElement>>,
+    /// The absolute path of the schema the macro was checked against, if any.
+    schema_path: Option<String>,
+}
+
+#[derive(Clone)]
+enum Key {
+    /// A key written as identifiers joined with `:`, `.` and `*`, or as a string literal.
+    Literal(String),
+    /// A key computed at runtime from `[$expr]`.
+    Computed(Expr),
+}
+
+impl ToTokens for Key {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(match self {
+            Key::Literal(key) => quote! { #key.to_string() },
+            Key::Computed(expr) => quote! { ::std::string::ToString::to_string(&(#expr)) },
+        })
+    }
 }
 
 #[derive(Clone)]
 struct SJsonElement {
-    id: String,
+    id: Key,
+    /// The tokens the key was written as, used to underline the whole key in schema errors.
+    key_tokens: TokenStream,
     value: SJsonValue,
-    direct_inclusion: Option<TokenStream>,
+    direct_inclusion: Option<Expr>,
+}
+
+/// An entry of an object or an array: either a plain element or value, or control flow producing any number of them.
+#[derive(Clone)]
+enum Entry<T> {
+    Item(T),
+    If {
+        condition: Expr,
+        then: Vec<Entry<T>>,
+        otherwise: Vec<Entry<T>>,
+    },
+    For {
+        pattern: Pat,
+        iterable: Expr,
+        body: Vec<Entry<T>>,
+    },
+    Spread(Expr),
+}
+
+/// The collection the generated code pushes entries into.
+#[derive(Clone, Copy)]
+enum Sink {
+    /// The `Vec<SJsonElement>` at the top level of `sjson!`.
+    Elements,
+    Object,
+    Array,
 }
 
+impl Sink {
+    fn ident(self) -> Ident {
+        let name = match self {
+            Sink::Elements => "elements",
+            Sink::Object => "object",
+            Sink::Array => "array",
+        };
+
+        Ident::new(name, Span::mixed_site())
+    }
+}
 
+
+/// A value written in the macro. Every variant keeps the span it was written at.
 #[derive(Clone)]
 enum SJsonValue {
-    String(String),
-    Number(f64),
-    Object(Vec<SJsonElement>),
-    Array(Vec<SJsonValue>),
-    Variable(Ident),
-    Bool(bool)
+    String(String, Span),
+    Integer(i128, Span),
+    Float(f64, Span),
+    Object(Vec<Entry<SJsonElement>>, Span),
+    Array(Vec<Entry<SJsonValue>>, Span),
+    Interpolation(Expr),
+    Bool(bool, Span),
+    Null(Span),
+}
+
+impl SJsonValue {
+    fn literal(&self) -> Option<Literal<'_>> {
+        Some(match self {
+            SJsonValue::String(v, _) => Literal::String(v),
+            SJsonValue::Integer(v, _) => Literal::Integer(*v),
+            SJsonValue::Float(v, _) => Literal::Float(*v),
+            SJsonValue::Object(..) => Literal::Object,
+            SJsonValue::Array(..) => Literal::Array,
+            SJsonValue::Bool(v, _) => Literal::Bool(*v),
+            SJsonValue::Null(_) => Literal::Null,
+            SJsonValue::Interpolation(_) => return None,
+        })
+    }
+
+    fn check(&self, schema: &Schema, errors: &mut Vec<syn::Error>) {
+        if let SJsonValue::Object(entries, span) = self {
+            return check_object(entries, *span, schema, errors);
+        }
+
+        let Some(literal) = self.literal() else { return };
+        let messages = schema.check(&literal);
+
+        if let (SJsonValue::Array(entries, _), Some(items), true) = (self, schema.items(), messages.is_empty()) {
+            check_array_entries(entries, items, errors);
+        }
+
+        errors.extend(messages.into_iter().map(|message| syn::Error::new(self.span(), message)));
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            SJsonValue::String(_, span) | SJsonValue::Integer(_, span) | SJsonValue::Float(_, span) | SJsonValue::Object(_, span)
+            | SJsonValue::Array(_, span) | SJsonValue::Bool(_, span) | SJsonValue::Null(span) => *span,
+            SJsonValue::Interpolation(expr) => expr.span(),
+        }
+    }
+}
+
+/// Whether the macro starts with `schema = "path", { ... }` rather than a plain `schema = "value"` entry.
+fn peek_schema(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    fork.parse::<Ident>().is_ok_and(|ident| ident == "schema")
+        && fork.parse::<Token![=]>().is_ok()
+        && fork.parse::<syn::LitStr>().is_ok()
+        && fork.parse::<Token![,]>().is_ok()
+        && fork.peek(syn::token::Brace)
 }
 
 impl Parse for SJsonMacro {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(Self {
-            jsons: Punctuated::<SJsonElement, Token![,]>::parse_terminated(input)?.into_iter().collect::<Vec<SJsonElement>>()
-        })
+        if !peek_schema(input) {
+            return Ok(Self {
+                jsons: parse_entries(input)?,
+                schema_path: None,
+            });
+        }
+
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let path = input.parse::<syn::LitStr>()?;
+        input.parse::<Token![,]>()?;
+
+        let span = input.span();
+        let contents;
+        braced!(contents in input);
+        let jsons = parse_entries(&contents)?;
+
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("expected the end of the macro after the object checked against the schema"));
+        }
+
+        let (schema, schema_path) = Schema::load(&path)?;
+        let mut errors = vec![];
+        check_object(&jsons, span, &schema, &mut errors);
+
+        match errors.into_iter().reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        }) {
+            Some(error) => Err(error),
+            None => Ok(Self { jsons, schema_path: Some(schema_path) }),
+        }
+    }
+}
+
+/// Check the entries of an object against `schema`. Only what is written literally is checked; interpolated values,
+/// computed keys, direct inclusions and spreads are only known at runtime. Required keys are only checked when every
+/// key is known, and keys inside `if` and `for` count as present.
+fn check_object(entries: &[Entry<SJsonElement>], span: Span, schema: &Schema, errors: &mut Vec<syn::Error>) {
+    let messages = schema.check(&Literal::Object);
+    if !messages.is_empty() {
+        errors.extend(messages.into_iter().map(|message| syn::Error::new(span, message)));
+        return;
+    }
+
+    let mut keys = vec![];
+    let mut complete = true;
+    check_object_entries(entries, schema, &mut keys, &mut complete, errors);
+
+    if complete {
+        for key in schema.required.iter().filter(|key| !keys.contains(key)) {
+            errors.push(syn::Error::new(span, format!("missing required property `{key}`")));
+        }
     }
 }
 
+fn check_object_entries(entries: &[Entry<SJsonElement>], schema: &Schema, keys: &mut Vec<String>, complete: &mut bool, errors: &mut Vec<syn::Error>) {
+    for entry in entries {
+        match entry {
+            Entry::Item(SJsonElement { id: Key::Literal(key), key_tokens, value, direct_inclusion: None }) => {
+                keys.push(key.clone());
+
+                match schema.property(key) {
+                    Ok(Some(property)) => value.check(property, errors),
+                    Ok(None) => {}
+                    Err(message) => errors.push(syn::Error::new_spanned(key_tokens, message)),
+                }
+            }
+            Entry::Item(_) | Entry::Spread(_) => *complete = false,
+            Entry::If { then, otherwise, .. } => {
+                check_object_entries(then, schema, keys, complete, errors);
+                check_object_entries(otherwise, schema, keys, complete, errors);
+            }
+            Entry::For { body, .. } => check_object_entries(body, schema, keys, complete, errors),
+        }
+    }
+}
+
+fn check_array_entries(entries: &[Entry<SJsonValue>], items: &Schema, errors: &mut Vec<syn::Error>) {
+    for entry in entries {
+        match entry {
+            Entry::Item(value) => value.check(items, errors),
+            Entry::If { then, otherwise, .. } => {
+                check_array_entries(then, items, errors);
+                check_array_entries(otherwise, items, errors);
+            }
+            Entry::For { body, .. } => check_array_entries(body, items, errors),
+            Entry::Spread(_) => {}
+        }
+    }
+}
+
+/// Parse comma separated entries until the end of the input. The comma after an `if` or `for` block is optional.
+fn parse_entries<T: Parse>(input: ParseStream) -> syn::Result<Vec<Entry<T>>> {
+    let mut entries = vec![];
+
+    while !input.is_empty() {
+        let entry = input.parse::<Entry<T>>()?;
+        let block = matches!(entry, Entry::If { .. } | Entry::For { .. });
+
+        entries.push(entry);
+
+        if input.is_empty() || (block && !input.peek(Token![,])) {
+            continue;
+        }
+
+        input.parse::<Token![,]>()?;
+    }
+
+    Ok(entries)
+}
+
+fn parse_block<T: Parse>(input: ParseStream) -> syn::Result<Vec<Entry<T>>> {
+    let contents;
+    braced!(contents in input);
+
+    parse_entries(&contents)
+}
+
+/// Parse `$ident`, `$ident.field`, `$path::CONST` or `$( expr )`.
+fn parse_interpolation(input: ParseStream) -> syn::Result<Expr> {
+    if !input.peek(Token![$]) {
+        return Err(input.error("expected `$` followed by a variable, a path or `( expr )`"));
+    }
+
+    let dollar = input.parse::<Token![$]>()?;
+
+    if input.peek(syn::token::Paren) {
+        let contents;
+        parenthesized!(contents in input);
+
+        return contents.parse::<Expr>();
+    }
+
+    if !(input.peek(syn::Ident) || input.peek(Token![::])) {
+        return Err(syn::Error::new(dollar.span, "expected a variable or path after `$`, e.g. `$value`, `$value.field` or `$Type::CONST`, or `$( expr )` for any expression"));
+    }
+
+    let mut expr = Expr::Path(ExprPath { attrs: vec![], qself: None, path: Path::parse_mod_style(input)? });
+
+    while input.peek(Token![.]) && (input.peek2(syn::Ident) || input.peek2(syn::LitInt)) {
+        let dot_token = input.parse::<Token![.]>()?;
+        let member = input.parse::<Member>()?;
+
+        expr = Expr::Field(ExprField { attrs: vec![], base: Box::new(expr), dot_token, member });
+    }
+
+    Ok(expr)
+}
+
+fn interpolate(expr: &Expr) -> TokenStream {
+    quote! { {
+        use eo::sjson::ToSJson as _;
+        (#expr).sjson()
+    } }
+}
+
+impl<T: Parse> Parse for Entry<T> {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+
+            let condition = parse_interpolation(input)?;
+            let then = parse_block(input)?;
+            let mut otherwise = vec![];
+
+            if input.peek(Token![else]) {
+                input.parse::<Token![else]>()?;
+
+                otherwise = if input.peek(Token![if]) {
+                    vec![input.parse::<Entry<T>>()?]
+                } else {
+                    parse_block(input)?
+                };
+            }
+
+            Ok(Entry::If { condition, then, otherwise })
+        } else if input.peek(Token![for]) {
+            input.parse::<Token![for]>()?;
+
+            if !input.peek(Token![$]) {
+                return Err(input.error("expected `$` followed by the loop pattern, e.g. `for $item in $items { ... }`"));
+            }
+            input.parse::<Token![$]>()?;
+
+            let pattern = Pat::parse_single(input)?;
+            input.parse::<Token![in]>()?;
+            let iterable = parse_interpolation(input)?;
+            let body = parse_block(input)?;
+
+            Ok(Entry::For { pattern, iterable, body })
+        } else if input.peek(Token![..]) {
+            input.parse::<Token![..]>()?;
+
+            Ok(Entry::Spread(parse_interpolation(input)?))
+        } else {
+            Ok(Entry::Item(input.parse::<T>()?))
+        }
+    }
+}
+
+trait EmitItem {
+    fn emit(&self, sink: Sink) -> TokenStream;
+}
+
+impl EmitItem for SJsonElement {
+    fn emit(&self, sink: Sink) -> TokenStream {
+        let target = sink.ident();
+        let SJsonElement { id, value, direct_inclusion, .. } = self;
+
+        match (sink, direct_inclusion) {
+            // Scoped, so the locals don't shadow user variables named `id` or `params` in later entries.
+            (Sink::Elements, Some(inclusion)) => quote! { {
+                let (id, params) = eo::sjson::direct_inclusion(&(#inclusion));
+                #target.push(eo::sjson::SJsonElement { id, params });
+            } },
+            (Sink::Elements, None) => quote! { #target.push(#self); },
+            (_, Some(inclusion)) => quote! { {
+                let (id, params) = eo::sjson::direct_inclusion(&(#inclusion));
+                #target.insert(id, params);
+            } },
+            (_, None) => quote! { #target.insert(#id, #value); },
+        }
+    }
+}
+
+impl EmitItem for SJsonValue {
+    fn emit(&self, sink: Sink) -> TokenStream {
+        let target = sink.ident();
+
+        quote! { #target.push(#self); }
+    }
+}
+
+impl<T: EmitItem> Entry<T> {
+    fn emit(&self, sink: Sink) -> TokenStream {
+        let target = sink.ident();
+
+        match self {
+            Entry::Item(item) => item.emit(sink),
+            Entry::If { condition, then, otherwise } => {
+                let then = then.iter().map(|entry| entry.emit(sink));
+                let otherwise = otherwise.iter().map(|entry| entry.emit(sink));
+
+                quote! {
+                    if #condition {
+                        #(#then)*
+                    } else {
+                        #(#otherwise)*
+                    }
+                }
+            }
+            Entry::For { pattern, iterable, body } => {
+                let body = body.iter().map(|entry| entry.emit(sink));
+
+                quote! {
+                    for #pattern in #iterable {
+                        #(#body)*
+                    }
+                }
+            }
+            Entry::Spread(expr) => {
+                // Spanned at the spread value, so a value of the wrong type is reported where it was written.
+                let spread = match sink {
+                    Sink::Elements | Sink::Object => quote_spanned! { expr.span()=> eo::sjson::SpreadObject::spread_object(&(#expr)) },
+                    Sink::Array => quote_spanned! { expr.span()=> eo::sjson::SpreadArray::spread_array(&(#expr)) },
+                };
+
+                match sink {
+                    Sink::Elements => quote! {
+                        for (id, params) in #spread {
+                            #target.push(eo::sjson::SJsonElement { id, params });
+                        }
+                    },
+                    Sink::Object | Sink::Array => quote! { #target.extend(#spread); },
+                }
+            }
+        }
+    }
+}
+
+/// Collect the tokens from `start` up to, but not including, `end`.
+fn tokens_between(start: Cursor, end: Cursor) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    let mut cursor = start;
+
+    while cursor != end {
+        let Some((token, next)) = cursor.token_tree() else { break };
+        tokens.extend([token]);
+        cursor = next;
+    }
+
+    tokens
+}
+
 impl Parse for SJsonElement {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         // let name_punct =
@@ -42,23 +447,50 @@ impl Parse for SJsonElement {
         //         .into_iter().map(|x| x.to_string()).collect::<Vec<String>>();
 
 
+        let key_span = input.span();
+        let key_start = input.cursor();
+
         if input.peek(Token![:]) {
             input.parse::<Token![:]>()?;
             let inclusion = input.parse::<Expr>()?;
-            let serialized = quote! { {
-                use serde_json::to_string;
-                let built = #inclusion;
-                (built.sjson_ident(), to_string(&built).unwrap())
-            } };
             return Ok(
                 SJsonElement {
-                    id: "".to_string(),
-                    value: SJsonValue::Bool(false),
-                    direct_inclusion: Some(serialized)
+                    id: Key::Literal("".to_string()),
+                    key_tokens: TokenStream::new(),
+                    value: SJsonValue::Null(key_span),
+                    direct_inclusion: Some(inclusion)
                 }
             )
         }
 
+        if input.peek(syn::LitStr) || input.peek(syn::token::Bracket) {
+            let id = if input.peek(syn::LitStr) {
+                Key::Literal(input.parse::<syn::LitStr>()?.value())
+            } else {
+                let contents;
+                bracketed!(contents in input);
+
+                let expr = parse_interpolation(&contents)?;
+                if !contents.is_empty() {
+                    return Err(contents.error("expected `]` after the computed key"));
+                }
+
+                Key::Computed(expr)
+            };
+            let key_tokens = tokens_between(key_start, input.cursor());
+
+            if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+            }
+
+            return Ok(SJsonElement {
+                id,
+                key_tokens,
+                value: input.parse::<SJsonValue>()?,
+                direct_inclusion: None
+            });
+        }
+
         let name_parsed = {
             let mut parsed: Vec<String> = vec![];
 
@@ -84,17 +516,22 @@ impl Parse for SJsonElement {
             parsed
         };
 
+        if name_parsed.is_empty() {
+            return Err(input.error("expected a key, e.g. `name = value`, `minecraft:icon = value`, `\"any string\" = value` or `[$expr] = value`, or `: expr` for a direct inclusion"));
+        }
+
         let name = name_parsed.join("");
+        let key_tokens = tokens_between(key_start, input.cursor());
 
-        if input.peek2(syn::LitStr) || input.peek2(syn::LitFloat) ||
-            input.peek2(syn::LitInt) || input.peek2(syn::LitBool) || input.peek2(Token![$]) {
+        if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
         }
 
         let value = input.parse::<SJsonValue>()?;
 
         Ok(SJsonElement {
-            id: name,
+            id: Key::Literal(name),
+            key_tokens,
             value,
             direct_inclusion: None
         })
@@ -103,43 +540,60 @@ impl Parse for SJsonElement {
 
 impl Parse for SJsonValue {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        if input.peek(syn::LitStr) {
-            Ok(Self::String(input.parse::<syn::LitStr>()?.value()))
-        } else if input.peek(syn::LitInt) {
-            Ok(Self::Number(input.parse::<syn::LitInt>()?.base10_parse::<f64>()?))
-        } else if input.peek(syn::LitFloat) {
-            Ok(Self::Number(input.parse::<syn::LitFloat>()?.base10_parse::<f64>()?))
-        } else if input.peek(syn::LitBool) {
-            Ok(Self::Bool(input.parse::<syn::LitBool>()?.value()))
-        } else if input.peek(Token![$]) {
-            input.parse::<Token![$]>()?;
-
-            Ok(Self::Variable(input.parse::<Ident>()?))
-        } else if let Some((_, d, _, _)) = input.cursor().any_group() {
-            if (d == Delimiter::Brace) {
-                let contents;
-                braced!(contents in input);
+        let span = input.span();
+        let negative = input.peek(Token![-]) && (input.peek2(syn::LitInt) || input.peek2(syn::LitFloat));
 
-                let t: Punctuated<SJsonElement, Token![,]> = contents.parse_terminated(SJsonElement::parse, Token![,])?;
+        if negative {
+            input.parse::<Token![-]>()?;
+        }
 
+        if input.peek(syn::LitStr) {
+            Ok(Self::String(input.parse::<syn::LitStr>()?.value(), span))
+        } else if input.peek(syn::LitInt) {
+            let lit = input.parse::<syn::LitInt>()?;
+            let v = lit.base10_parse::<i128>()?;
+            let v = if negative { -v } else { v };
 
-                let p = t.into_iter().collect::<Vec<SJsonElement>>();
+            if i64::try_from(v).is_err() && u64::try_from(v).is_err() {
+                return Err(syn::Error::new(lit.span(), "integer literal does not fit into 64 bits"));
+            }
 
-                Ok(Self::Object(p))
-            } else if (d == Delimiter::Bracket) {
-                let contents;
-                bracketed!(contents in input);
+            Ok(Self::Integer(v, span))
+        } else if input.peek(syn::LitFloat) {
+            let lit = input.parse::<syn::LitFloat>()?;
+            let v = lit.base10_parse::<f64>()?;
 
-                let t: Punctuated<SJsonValue, Token![,]> = contents.parse_terminated(SJsonValue::parse, Token![,])?;
+            if !v.is_finite() {
+                return Err(syn::Error::new(lit.span(), "float literal is out of range for 64 bits"));
+            }
 
-                let p = t.into_iter().collect::<Vec<SJsonValue>>();
+            Ok(Self::Float(if negative { -v } else { v }, span))
+        } else if input.peek(syn::LitBool) {
+            Ok(Self::Bool(input.parse::<syn::LitBool>()?.value(), span))
+        } else if input.cursor().ident().is_some_and(|(ident, _)| ident == "null") {
+            input.parse::<Ident>()?;
 
-                Ok(Self::Array(p))
-            } else {
-                panic!("Not a valid sJSON syntax.")
-            }
+            Ok(Self::Null(span))
+        } else if input.peek(Token![$]) {
+            Ok(Self::Interpolation(parse_interpolation(input)?))
+        } else if input.peek(syn::token::Brace) {
+            let contents;
+            braced!(contents in input);
+
+            Ok(Self::Object(parse_entries(&contents)?, span))
+        } else if input.peek(syn::token::Bracket) {
+            let contents;
+            bracketed!(contents in input);
+
+            Ok(Self::Array(parse_entries(&contents)?, span))
+        } else if input.peek(syn::token::Paren) {
+            let (_, span, _) = input.cursor().group(Delimiter::Parenthesis).unwrap();
+
+            Err(syn::Error::new(span.join(), "parentheses are not valid sJSON syntax; use `{ ... }` for an object or `[ ... ]` for an array"))
+        } else if let Some((ident, _)) = input.cursor().ident() {
+            Err(syn::Error::new(ident.span(), format!("`{ident}` is not an sJSON value; use `${ident}` to insert a variable or `\"{ident}\"` for a string")))
         } else {
-            panic!("Not a valid sJSON syntax.")
+            Err(input.error("expected an sJSON value; values are strings, numbers, booleans, `null`, `$variable`s, `{ ... }` objects or `[ ... ]` arrays"))
         }
     }
 }
@@ -148,94 +602,76 @@ impl ToTokens for SJsonValue {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.append_all(
             match &self {
-                SJsonValue::String(v) => {
+                SJsonValue::String(v, _) => {
                     quote! { eo::sjson::SJsonValue::String(#v.to_string()) }
                 },
-                SJsonValue::Number(v) => {
-                    quote! { eo::sjson::SJsonValue::Number(#v) }
-                },
-                SJsonValue::Object(v) => {
-                    let convert: Vec<SJsonElementHashMap> = v.into_iter().map(|x| SJsonElementHashMap::from(x.clone())).collect();
-
-                    quote! {
-                        eo::sjson::SJsonValue::Object(::std::collections::HashMap::from([
-                            #(#convert),*
-                        ]))
+                SJsonValue::Integer(v, _) => {
+                    match i64::try_from(*v) {
+                        Ok(v) => quote! { eo::sjson::SJsonValue::Number(eo::sjson::SJsonNumber::Int(#v)) },
+                        Err(_) => {
+                            let v = *v as u64;
+                            quote! { eo::sjson::SJsonValue::Number(eo::sjson::SJsonNumber::UInt(#v)) }
+                        }
                     }
+                },
+                SJsonValue::Float(v, _) => {
+                    quote! { eo::sjson::SJsonValue::Number(eo::sjson::SJsonNumber::Float(#v)) }
+                },
+                SJsonValue::Object(v, _) => {
+                    let target = Sink::Object.ident();
+                    let entries = v.iter().map(|entry| entry.emit(Sink::Object));
+
+                    quote! { {
+                        let mut #target = eo::sjson::SJsonObject::new();
+                        #(#entries)*
+                        eo::sjson::SJsonValue::Object(#target)
+                    } }
                 }
-                SJsonValue::Array(v) => {
-                    quote! {
-                        eo::sjson::SJsonValue::Array(::std::vec::Vec::<eo::sjson::SJsonValue>::from([
-                            #(#v),*
-                        ]))
-                    }
+                SJsonValue::Array(v, _) => {
+                    let target = Sink::Array.ident();
+                    let entries = v.iter().map(|entry| entry.emit(Sink::Array));
+
+                    quote! { {
+                        let mut #target = ::std::vec::Vec::<eo::sjson::SJsonValue>::new();
+                        #(#entries)*
+                        eo::sjson::SJsonValue::Array(#target)
+                    } }
                 },
-                SJsonValue::Variable(ident) => quote! { #ident.sjson() },
-                SJsonValue::Bool(v) => quote! { eo::sjson::SJsonValue::Boolean(#v) },
+                SJsonValue::Interpolation(expr) => interpolate(expr),
+                SJsonValue::Bool(v, _) => quote! { eo::sjson::SJsonValue::Boolean(#v) },
+                SJsonValue::Null(_) => quote! { eo::sjson::SJsonValue::Null },
             }
         )
     }
 }
 
-struct SJsonElementHashMap {
-    id: String,
-    value: SJsonValue
-}
-
-impl ToTokens for SJsonElementHashMap {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        let SJsonElementHashMap { id, value } = self;
-        tokens.append_all(
-            quote! {
-                (#id.to_string(), #value)
-            }
-        )
-    }
-}
-
-impl From<SJsonElement> for SJsonElementHashMap {
-    fn from(value: SJsonElement) -> Self {
-        Self {
-            value: value.value,
-            id: value.id
-        }
-    }
-}
-
 impl ToTokens for SJsonElement {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let SJsonElement { id, value, direct_inclusion } = self;
-        if let Some(inc) = direct_inclusion {
-            tokens.append_all(
-                quote! {
-                    eo::sjson::SJsonElement {
-                        id: "".to_string(),
-                        params: SJsonValue::Boolean(false),
-                        direct: Some(#inc)
-                    }
-                }
-            );
-        } else {
-            tokens.append_all(quote! {
-                eo::sjson::SJsonElement {
-                    id: #id.to_string(),
-                    params: #value,
-                    direct: None
-                }
-            })
-        }
+        let SJsonElement { id, value, .. } = self;
+
+        tokens.append_all(quote! {
+            eo::sjson::SJsonElement {
+                id: #id,
+                params: #value
+            }
+        })
     }
 }
 
 impl ToTokens for SJsonMacro {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let q = &self.jsons;
+        let target = Sink::Elements.ident();
+        let entries = self.jsons.iter().map(|entry| entry.emit(Sink::Elements));
+        // Makes the build depend on the schema file, so changing it checks the macro again.
+        let schema = self.schema_path.iter().map(|path| quote! { const _: &[u8] = include_bytes!(#path); });
+
         tokens.append_all(
-            quote! {
-                std::vec::Vec::<eo::sjson::SJsonElement>::from([
-                    #(#q),*
-                ])
-            }
+            quote! { {
+                #(#schema)*
+                let mut #target = ::std::vec::Vec::<eo::sjson::SJsonElement>::new();
+                #(#entries)*
+                #target
+            } }
         )
     }
 }
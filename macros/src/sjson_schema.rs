@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+use serde_json::{Map, Value};
+use syn::LitStr;
+use crate::suggest::closest_key;
+
+/// The subset of JSON Schema `sjson!(schema = "...", { ... })` checks at compile time: `type`, `required`,
+/// `properties`, `additionalProperties`, `enum`, `minimum`, `maximum` and `items`. Anything else, like `pattern`,
+/// is left for `eo::sjson::Schema::validate` at runtime.
+pub struct Schema {
+    types: Vec<String>,
+    pub required: Vec<String>,
+    properties: Vec<(String, Schema)>,
+    additional_properties: bool,
+    enumeration: Option<Vec<Value>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    items: Option<Box<Schema>>,
+}
+
+/// A value written literally in the macro, as far as a schema is concerned.
+pub enum Literal<'a> {
+    String(&'a str),
+    Integer(i128),
+    Float(f64),
+    Bool(bool),
+    Null,
+    Object,
+    Array,
+}
+
+impl Literal<'_> {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Literal::String(_) => "string",
+            Literal::Integer(_) | Literal::Float(_) => "number",
+            Literal::Bool(_) => "boolean",
+            Literal::Null => "null",
+            Literal::Object => "object",
+            Literal::Array => "array",
+        }
+    }
+
+    fn matches(&self, schema_type: &str) -> bool {
+        match (schema_type, self) {
+            ("integer", Literal::Integer(_)) => true,
+            ("integer", Literal::Float(v)) => v.fract() == 0.0,
+            ("integer", _) => false,
+            (other, literal) => other == literal.type_name(),
+        }
+    }
+
+    fn to_json(&self) -> Option<Value> {
+        match *self {
+            Literal::String(v) => Some(Value::String(v.to_string())),
+            Literal::Integer(v) => i64::try_from(v).ok().map(Value::from).or_else(|| u64::try_from(v).ok().map(Value::from)),
+            Literal::Float(v) => serde_json::Number::from_f64(v).map(Value::Number),
+            Literal::Bool(v) => Some(Value::Bool(v)),
+            Literal::Null => Some(Value::Null),
+            Literal::Object | Literal::Array => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Literal::Integer(v) => Some(v as f64),
+            Literal::Float(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl Schema {
+    /// Read the schema file at `path`, relative to the file the macro is used in, like `include_str!`.
+    /// Returns the schema and the absolute path, so the caller can make the build depend on the file.
+    pub fn load(path: &LitStr) -> syn::Result<(Schema, String)> {
+        let root = path.span().unwrap().local_file()
+            .and_then(|file| file.parent().map(PathBuf::from))
+            .or_else(|| std::env::var("CARGO_MANIFEST_DIR").ok().map(PathBuf::from))
+            .unwrap_or_default();
+        let full_path = root.join(path.value());
+        let full_path = std::path::absolute(&full_path).unwrap_or(full_path);
+        let error = |message: String| syn::Error::new(path.span(), message);
+
+        let source = std::fs::read_to_string(&full_path)
+            .map_err(|e| error(format!("couldn't read the schema `{}`: {e}", full_path.display())))?;
+        let json = serde_json::from_str(&source)
+            .map_err(|e| error(format!("the schema `{}` is not valid JSON: {e}", path.value())))?;
+        let schema = Schema::from_json(&json, "")
+            .map_err(|e| error(format!("invalid schema `{}`: {e}", path.value())))?;
+
+        Ok((schema, full_path.to_string_lossy().into_owned()))
+    }
+
+    fn from_json(json: &Value, path: &str) -> Result<Schema, String> {
+        let at = |key: &str| if path.is_empty() { key.to_string() } else { format!("{path}.{key}") };
+
+        let object = match json {
+            Value::Bool(true) => &Map::new(),
+            Value::Object(object) => object,
+            _ => return Err(format!("{}: expected object", if path.is_empty() { "(root)" } else { path })),
+        };
+
+        // Malformed keywords are rejected like `Schema::from_sjson` does, so a file is never stricter at runtime.
+        let field = |key: &str| object.get(key).filter(|value| !value.is_null());
+        let expected = |key: &str, expected: &str, found: &Value| format!("{}: expected {expected}, found {}", at(key), json_type(found));
+
+        let types = match object.get("type") {
+            None => vec![],
+            Some(Value::String(t)) => vec![t.clone()],
+            Some(Value::Array(types)) => types.iter().map(|t| t.as_str().map(str::to_string).ok_or_else(|| format!("{}: expected a string", at("type")))).collect::<Result<_, _>>()?,
+            Some(_) => return Err(format!("{}: expected a string or an array", at("type"))),
+        };
+
+        if let Some(unknown) = types.iter().find(|t| !matches!(t.as_str(), "string" | "number" | "integer" | "boolean" | "object" | "array" | "null")) {
+            return Err(format!("{}: unknown type `{unknown}`", at("type")));
+        }
+
+        let properties = match field("properties") {
+            None => vec![],
+            Some(Value::Object(properties)) => properties.iter()
+                .map(|(key, property)| Ok((key.clone(), Schema::from_json(property, &at(&format!("properties.{key}")))?)))
+                .collect::<Result<_, String>>()?,
+            Some(other) => return Err(expected("properties", "object", other)),
+        };
+
+        let required = match field("required") {
+            None => vec![],
+            Some(Value::Array(required)) => required.iter().enumerate()
+                .map(|(i, key)| key.as_str().map(str::to_string).ok_or_else(|| expected(&format!("required[{i}]"), "string", key)))
+                .collect::<Result<_, _>>()?,
+            Some(other) => return Err(expected("required", "array", other)),
+        };
+        let additional_properties = match field("additionalProperties") {
+            None => true,
+            Some(Value::Bool(allowed)) => *allowed,
+            Some(other) => return Err(expected("additionalProperties", "boolean", other)),
+        };
+        let enumeration = match field("enum") {
+            None => None,
+            Some(Value::Array(values)) => Some(values.clone()),
+            Some(other) => return Err(expected("enum", "array", other)),
+        };
+        let number = |key: &str| match field(key) {
+            None => Ok(None),
+            Some(Value::Number(number)) => Ok(number.as_f64()),
+            Some(other) => Err(expected(key, "number", other)),
+        };
+
+        if let Some(pattern) = field("pattern").filter(|pattern| !pattern.is_string()) {
+            return Err(expected("pattern", "string", pattern));
+        }
+
+        Ok(Schema {
+            types,
+            required,
+            properties,
+            additional_properties,
+            enumeration,
+            minimum: number("minimum")?,
+            maximum: number("maximum")?,
+            items: field("items").map(|items| Schema::from_json(items, &at("items")).map(Box::new)).transpose()?,
+        })
+    }
+
+    /// The schema of the property `key`, `Ok(None)` if any value is allowed, or an error message if the key isn't allowed.
+    pub fn property(&self, key: &str) -> Result<Option<&Schema>, String> {
+        match self.properties.iter().find(|(name, _)| name == key) {
+            Some((_, property)) => Ok(Some(property)),
+            None if self.additional_properties => Ok(None),
+            None => Err(match closest_key(key, self.properties.iter().map(|(name, _)| name)) {
+                Some(suggestion) => format!("unknown property `{key}`, did you mean `{suggestion}`?"),
+                None => format!("unknown property `{key}`"),
+            }),
+        }
+    }
+
+    pub fn items(&self) -> Option<&Schema> {
+        self.items.as_deref()
+    }
+
+    /// Messages for every way `literal` doesn't match this schema.
+    pub fn check(&self, literal: &Literal) -> Vec<String> {
+        if !self.types.is_empty() && !self.types.iter().any(|t| literal.matches(t)) {
+            return vec![format!("expected {}, found {}", self.types.join(" or "), literal.type_name())];
+        }
+
+        let mut messages = vec![];
+
+        if let (Some(values), Some(json)) = (&self.enumeration, literal.to_json())
+            && !values.iter().any(|allowed| json_eq(allowed, &json)) {
+            let allowed = values.iter().map(ToString::to_string).collect::<Vec<String>>().join(", ");
+            messages.push(format!("{json} is not one of {allowed}"));
+        }
+
+        if let Some(number) = literal.as_f64() {
+            if let Some(minimum) = self.minimum.filter(|minimum| number < *minimum) {
+                messages.push(format!("{number} is less than the minimum of {minimum}"));
+            }
+            if let Some(maximum) = self.maximum.filter(|maximum| number > *maximum) {
+                messages.push(format!("{number} is greater than the maximum of {maximum}"));
+            }
+        }
+
+        messages
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+    }
+}
+
+/// Compares numbers by value, so `1` matches `1.0` in an `enum` as it does at runtime.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    let integer = |number: &serde_json::Number| number.as_i64().map(i128::from).or_else(|| number.as_u64().map(i128::from));
+
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (integer(a), integer(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.as_f64() == b.as_f64(),
+        },
+        (a, b) => a == b,
+    }
+}
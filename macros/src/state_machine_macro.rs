@@ -0,0 +1,87 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Token, Type};
+
+pub struct StateMachineMacro {
+    name: Ident,
+    state_type: Type,
+    event_type: Type,
+    initial_state: Expr,
+    transitions: Punctuated<TransitionDeclaration, Token![;]>,
+}
+
+struct TransitionDeclaration {
+    from: Expr,
+    to: Expr,
+    event: Expr,
+    guard: Option<Expr>,
+}
+
+impl Parse for StateMachineMacro {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let state_type: Type = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let event_type: Type = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let initial_state: Expr = input.parse()?;
+
+        let transitions = if input.is_empty() {
+            Punctuated::new()
+        } else {
+            input.parse::<Token![;]>()?;
+            Punctuated::<TransitionDeclaration, Token![;]>::parse_terminated(input)?
+        };
+
+        Ok(Self { name, state_type, event_type, initial_state, transitions })
+    }
+}
+
+impl Parse for TransitionDeclaration {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let from: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let to: Expr = input.parse()?;
+
+        let on: Ident = input.parse()?;
+        if on != "on" {
+            return Err(syn::Error::new(on.span(), "expected `on` followed by the event triggering the transition"));
+        }
+
+        let event: Expr = input.parse()?;
+
+        let mut guard: Option<Expr> = None;
+
+        if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+            guard = Some(input.parse()?);
+        }
+
+        Ok(Self { from, to, event, guard })
+    }
+}
+
+impl ToTokens for StateMachineMacro {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let StateMachineMacro { name, state_type, event_type, initial_state, transitions } = self;
+
+        let transitions = transitions.iter().map(|TransitionDeclaration { from, to, event, guard }| {
+            match guard {
+                None => quote! {
+                    #name.add_transition(#from, #event, #to);
+                },
+                Some(guard) => quote! {
+                    #name.add_guarded_transition(#from, #event, #to, #guard);
+                },
+            }
+        });
+
+        tokens.append_all(quote! {
+            let #name: eo::state_machine::StateMachine<#state_type, #event_type> = eo::state_machine::StateMachine::new(#initial_state);
+            #(#transitions)*
+        })
+    }
+}
@@ -0,0 +1,330 @@
+pub mod components;
+
+use crate::sjson::{direct_inclusion, HasSJsonIdent, SJsonObject, SJsonValue, ToSJson};
+
+/// The `format_version` documents are created with unless another one is set.
+pub const FORMAT_VERSION: &str = "1.21.0";
+
+/// The creative inventory tab an item or block is listed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, crate::sjson::ToSJson)]
+#[sjson(rename_all = "lowercase")]
+pub enum Category {
+    Construction,
+    Equipment,
+    Items,
+    Nature,
+    None,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuCategory {
+    pub category: Category,
+    /// The expandable group within the tab, like `minecraft:itemGroup.name.sword`.
+    pub group: Option<String>,
+}
+
+impl MenuCategory {
+    pub fn new(category: Category) -> Self {
+        Self { category, group: None }
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+}
+
+impl ToSJson for MenuCategory {
+    fn sjson(&self) -> SJsonValue {
+        let mut object = SJsonObject::new();
+        object.insert("category".to_string(), self.category.sjson());
+        if let Some(group) = &self.group {
+            object.insert("group".to_string(), group.sjson());
+        }
+
+        SJsonValue::Object(object)
+    }
+}
+
+/// The `components` of an item, block or entity, keyed by the identifier of each component.
+/// Adding a component with an identifier which is already present replaces it in place.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Components {
+    components: SJsonObject,
+}
+
+impl Components {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<C>(mut self, component: C) -> Self
+    where
+        C: HasSJsonIdent + ToSJson {
+        self.insert(&component);
+        self
+    }
+
+    pub fn insert<C>(&mut self, component: &C)
+    where
+        C: HasSJsonIdent + ToSJson + ?Sized {
+        let (id, value) = direct_inclusion(component);
+        self.components.insert(id, value);
+    }
+
+    /// Add a component there is no typed builder for.
+    pub fn insert_raw(&mut self, id: impl Into<String>, value: SJsonValue) {
+        self.components.insert(id.into(), value);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SJsonValue> {
+        self.components.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl ToSJson for Components {
+    fn sjson(&self) -> SJsonValue {
+        SJsonValue::Object(self.components.clone())
+    }
+}
+
+/// A whole add-on file: `{ "format_version": ..., "minecraft:item": { ... } }`.
+fn document<T>(format_version: &str, value: &T) -> SJsonValue
+where
+    T: HasSJsonIdent + ToSJson {
+    let (id, value) = direct_inclusion(value);
+
+    let mut object = SJsonObject::new();
+    object.insert("format_version".to_string(), format_version.sjson());
+    object.insert(id, value);
+
+    SJsonValue::Object(object)
+}
+
+fn description(identifier: &str, menu_category: &Option<MenuCategory>) -> SJsonObject {
+    let mut description = SJsonObject::new();
+    description.insert("identifier".to_string(), identifier.sjson());
+    if let Some(menu_category) = menu_category {
+        description.insert("menu_category".to_string(), menu_category.sjson());
+    }
+
+    description
+}
+
+/// A behavior pack item, included in `sjson!` as `minecraft:item`.
+/// ```rust
+/// use eo::bedrock::components::{Damage, Icon};
+/// use eo::bedrock::{Category, Item, MenuCategory};
+///
+/// let sword = Item::new("eo:sword")
+///     .menu_category(MenuCategory::new(Category::Equipment))
+///     .component(Damage { value: 7 })
+///     .component(Icon("eo_sword".to_string()));
+///
+/// let file = sword.document();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    pub format_version: String,
+    pub identifier: String,
+    pub menu_category: Option<MenuCategory>,
+    pub components: Components,
+}
+
+impl Item {
+    pub fn new(identifier: impl Into<String>) -> Self {
+        Self { format_version: FORMAT_VERSION.to_string(), identifier: identifier.into(), menu_category: None, components: Components::new() }
+    }
+
+    pub fn format_version(mut self, format_version: impl Into<String>) -> Self {
+        self.format_version = format_version.into();
+        self
+    }
+
+    pub fn menu_category(mut self, menu_category: MenuCategory) -> Self {
+        self.menu_category = Some(menu_category);
+        self
+    }
+
+    pub fn component<C>(mut self, component: C) -> Self
+    where
+        C: HasSJsonIdent + ToSJson {
+        self.components.insert(&component);
+        self
+    }
+
+    /// The whole item file, including `format_version`.
+    pub fn document(&self) -> SJsonValue {
+        document(&self.format_version, self)
+    }
+}
+
+impl HasSJsonIdent for Item {
+    fn sjson_ident(&self) -> String {
+        "minecraft:item".to_string()
+    }
+}
+
+impl ToSJson for Item {
+    fn sjson(&self) -> SJsonValue {
+        let mut object = SJsonObject::new();
+        object.insert("description".to_string(), SJsonValue::Object(description(&self.identifier, &self.menu_category)));
+        object.insert("components".to_string(), self.components.sjson());
+
+        SJsonValue::Object(object)
+    }
+}
+
+/// A behavior pack block, included in `sjson!` as `minecraft:block`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub format_version: String,
+    pub identifier: String,
+    pub menu_category: Option<MenuCategory>,
+    pub components: Components,
+}
+
+impl Block {
+    pub fn new(identifier: impl Into<String>) -> Self {
+        Self { format_version: FORMAT_VERSION.to_string(), identifier: identifier.into(), menu_category: None, components: Components::new() }
+    }
+
+    pub fn format_version(mut self, format_version: impl Into<String>) -> Self {
+        self.format_version = format_version.into();
+        self
+    }
+
+    pub fn menu_category(mut self, menu_category: MenuCategory) -> Self {
+        self.menu_category = Some(menu_category);
+        self
+    }
+
+    pub fn component<C>(mut self, component: C) -> Self
+    where
+        C: HasSJsonIdent + ToSJson {
+        self.components.insert(&component);
+        self
+    }
+
+    /// The whole block file, including `format_version`.
+    pub fn document(&self) -> SJsonValue {
+        document(&self.format_version, self)
+    }
+}
+
+impl HasSJsonIdent for Block {
+    fn sjson_ident(&self) -> String {
+        "minecraft:block".to_string()
+    }
+}
+
+impl ToSJson for Block {
+    fn sjson(&self) -> SJsonValue {
+        let mut object = SJsonObject::new();
+        object.insert("description".to_string(), SJsonValue::Object(description(&self.identifier, &self.menu_category)));
+        object.insert("components".to_string(), self.components.sjson());
+
+        SJsonValue::Object(object)
+    }
+}
+
+/// A behavior pack entity, included in `sjson!` as `minecraft:entity`.
+/// Component groups and events are only written when there are any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entity {
+    pub format_version: String,
+    pub identifier: String,
+    pub is_spawnable: bool,
+    pub is_summonable: bool,
+    pub component_groups: Vec<(String, Components)>,
+    pub components: Components,
+    pub events: SJsonObject,
+}
+
+impl Entity {
+    pub fn new(identifier: impl Into<String>) -> Self {
+        Self {
+            format_version: FORMAT_VERSION.to_string(),
+            identifier: identifier.into(),
+            is_spawnable: true,
+            is_summonable: true,
+            component_groups: vec![],
+            components: Components::new(),
+            events: SJsonObject::new(),
+        }
+    }
+
+    pub fn format_version(mut self, format_version: impl Into<String>) -> Self {
+        self.format_version = format_version.into();
+        self
+    }
+
+    pub fn spawnable(mut self, is_spawnable: bool) -> Self {
+        self.is_spawnable = is_spawnable;
+        self
+    }
+
+    pub fn summonable(mut self, is_summonable: bool) -> Self {
+        self.is_summonable = is_summonable;
+        self
+    }
+
+    pub fn component<C>(mut self, component: C) -> Self
+    where
+        C: HasSJsonIdent + ToSJson {
+        self.components.insert(&component);
+        self
+    }
+
+    /// Add a component group, which events can add to or remove from the entity.
+    pub fn component_group(mut self, name: impl Into<String>, components: Components) -> Self {
+        self.component_groups.push((name.into(), components));
+        self
+    }
+
+    /// Add an event. Events have too many shapes for a typed builder, so they are written as sJSON.
+    pub fn event(mut self, name: impl Into<String>, event: SJsonValue) -> Self {
+        self.events.insert(name.into(), event);
+        self
+    }
+
+    /// The whole entity file, including `format_version`.
+    pub fn document(&self) -> SJsonValue {
+        document(&self.format_version, self)
+    }
+}
+
+impl HasSJsonIdent for Entity {
+    fn sjson_ident(&self) -> String {
+        "minecraft:entity".to_string()
+    }
+}
+
+impl ToSJson for Entity {
+    fn sjson(&self) -> SJsonValue {
+        let mut description = description(&self.identifier, &None);
+        description.insert("is_spawnable".to_string(), self.is_spawnable.sjson());
+        description.insert("is_summonable".to_string(), self.is_summonable.sjson());
+
+        let mut object = SJsonObject::new();
+        object.insert("description".to_string(), SJsonValue::Object(description));
+        if !self.component_groups.is_empty() {
+            let groups = self.component_groups.iter().map(|(name, components)| (name.clone(), components.sjson())).collect();
+            object.insert("component_groups".to_string(), SJsonValue::Object(groups));
+        }
+        object.insert("components".to_string(), self.components.sjson());
+        if !self.events.is_empty() {
+            object.insert("events".to_string(), SJsonValue::Object(self.events.clone()));
+        }
+
+        SJsonValue::Object(object)
+    }
+}
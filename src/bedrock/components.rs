@@ -0,0 +1,99 @@
+//! Typed Bedrock components. Each one knows its `minecraft:` identifier, so it can be added to [Components](crate::bedrock::Components)
+//! or included in `sjson!` with `: component`.
+
+use crate::sjson::{HasSJsonIdent, ToSJson};
+
+/// How much damage an item deals.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:damage")]
+pub struct Damage {
+    pub value: i32,
+}
+
+/// The texture shown for an item, as a short name from `item_texture.json`.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:icon")]
+pub struct Icon(pub String);
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:display_name")]
+pub struct DisplayName {
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:max_stack_size")]
+pub struct MaxStackSize(pub u8);
+
+/// Whether an item is held like a tool instead of a block.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:hand_equipped")]
+pub struct HandEquipped(pub bool);
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:glint")]
+pub struct Glint(pub bool);
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:durability")]
+pub struct Durability {
+    pub max_durability: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:destructible_by_mining")]
+pub struct DestructibleByMining {
+    pub seconds_to_destroy: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:destructible_by_explosion")]
+pub struct DestructibleByExplosion {
+    pub explosion_resistance: f64,
+}
+
+/// The light level a block emits, from 0 to 15.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:light_emission")]
+pub struct LightEmission(pub u8);
+
+/// The identifier of a block's model, like `geometry.lamp`.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:geometry")]
+pub struct Geometry(pub String);
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:friction")]
+pub struct Friction(pub f64);
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:health")]
+pub struct Health {
+    pub value: i32,
+    pub max: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:movement")]
+pub struct Movement {
+    pub value: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:collision_box")]
+pub struct CollisionBox {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The families an entity belongs to, used by filters like `is_family`.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:type_family")]
+pub struct TypeFamily {
+    pub family: Vec<String>,
+}
+
+/// Makes an entity affected by gravity and collisions.
+#[derive(Clone, Debug, PartialEq, ToSJson, HasSJsonIdent)]
+#[sjson(ident = "minecraft:physics")]
+pub struct Physics {}
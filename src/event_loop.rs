@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+use crate::events::Event;
+
+/// What an [EventLoop] does when a payload is queued while the queue is full.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackpressurePolicy {
+    /// Block the notifying thread until the loop has delivered some payloads.
+    Block,
+    /// Evict the oldest queued payload to make room for the new one.
+    DropOldest,
+    /// Refuse the new payload, returning it in [DispatchError::Full].
+    DropNewest,
+}
+
+/// Returned when a payload could not be queued. The payload is handed back.
+pub enum DispatchError<T> {
+    /// The queue is full and the policy is [BackpressurePolicy::DropNewest].
+    Full(T),
+    /// The loop was shut down.
+    Closed(T),
+}
+
+impl<T> DispatchError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            DispatchError::Full(v) | DispatchError::Closed(v) => v,
+        }
+    }
+}
+
+impl<T> Debug for DispatchError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispatchError::Full(_) => f.write_str("Full(..)"),
+            DispatchError::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
+}
+
+impl<T> Display for DispatchError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DispatchError::Full(_) => f.write_str("the event queue is full"),
+            DispatchError::Closed(_) => f.write_str("the event loop was shut down"),
+        }
+    }
+}
+
+impl<T> std::error::Error for DispatchError<T> {}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+struct Queue<T> {
+    state: Mutex<QueueState<T>>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> Queue<T> {
+    fn new(capacity: usize, policy: BackpressurePolicy) -> Self {
+        assert!(capacity > 0, "An EventLoop capacity must not be zero.");
+
+        Self {
+            state: Mutex::new(QueueState { items: VecDeque::with_capacity(capacity), closed: false }),
+            capacity,
+            policy,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    fn push(&self, value: T) -> Result<(), DispatchError<T>> {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if state.closed {
+                return Err(DispatchError::Closed(value));
+            }
+
+            if state.items.len() < self.capacity {
+                break;
+            }
+
+            match self.policy {
+                BackpressurePolicy::Block => state = self.not_full.wait(state).unwrap(),
+                BackpressurePolicy::DropOldest => {
+                    state.items.pop_front();
+                    break;
+                }
+                BackpressurePolicy::DropNewest => return Err(DispatchError::Full(value)),
+            }
+        }
+
+        state.items.push_back(value);
+        self.not_empty.notify_one();
+
+        Ok(())
+    }
+
+    fn drain(&self, wait: bool) -> Option<Vec<T>> {
+        let mut state = self.state.lock().unwrap();
+
+        while wait && state.items.is_empty() && !state.closed {
+            state = self.not_empty.wait(state).unwrap();
+        }
+
+        if state.items.is_empty() && state.closed {
+            return None;
+        }
+
+        let items = state.items.drain(..).collect();
+        self.not_full.notify_all();
+
+        Some(items)
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+/// The sending side of an [EventLoop]. Can be cloned and moved to other threads.
+pub struct EventSender<T> {
+    queue: Arc<Queue<T>>,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        Self { queue: self.queue.clone() }
+    }
+}
+
+impl<T> EventSender<T> {
+    /// Queue `value` for delivery by the loop.
+    pub fn notify(&self, value: T) -> Result<(), DispatchError<T>> {
+        self.queue.push(value)
+    }
+
+    /// Shut the loop down. Already queued payloads are still delivered, new ones are refused with [DispatchError::Closed].
+    pub fn shutdown(&self) {
+        self.queue.close();
+    }
+}
+
+/// An [Event] whose payloads are queued by [notify](EventLoop::notify) and delivered later by [run_pending](EventLoop::run_pending) or [run](EventLoop::run),
+/// always on the thread owning the loop.
+pub struct EventLoop<'a, T> {
+    event: Event<'a, T>,
+    queue: Arc<Queue<T>>,
+}
+
+impl<'a, T> EventLoop<'a, T> {
+    /// Create a new EventLoop holding at most `capacity` pending payloads.
+    pub fn new(capacity: usize, policy: BackpressurePolicy) -> Self {
+        Self::with_queue(Arc::new(Queue::new(capacity, policy)))
+    }
+
+    fn with_queue(queue: Arc<Queue<T>>) -> Self {
+        Self { event: Event::new(RwLock::new(Vec::new())), queue }
+    }
+
+    /// Create a sender which can queue payloads from any thread.
+    pub fn sender(&self) -> EventSender<T> {
+        EventSender { queue: self.queue.clone() }
+    }
+
+    pub fn subscribe(&self, callback: impl Fn(&T) + 'a) {
+        self.event.subscribe(callback);
+    }
+
+    /// Queue `value` for delivery. With [BackpressurePolicy::Block], calling this on a full queue from the loop's own thread blocks forever.
+    pub fn notify(&self, value: T) -> Result<(), DispatchError<T>> {
+        self.queue.push(value)
+    }
+
+    /// Deliver every queued payload on the calling thread without waiting for new ones. Returns the number of delivered payloads.
+    pub fn run_pending(&self) -> usize {
+        self.deliver(self.queue.drain(false).unwrap_or_default())
+    }
+
+    /// Deliver payloads as they arrive until the loop is shut down and the queue is empty.
+    pub fn run(&self) {
+        while let Some(items) = self.queue.drain(true) {
+            self.deliver(items);
+        }
+    }
+
+    /// Shut the loop down. See [EventSender::shutdown].
+    pub fn shutdown(&self) {
+        self.queue.close();
+    }
+
+    fn deliver(&self, items: Vec<T>) -> usize {
+        let count = items.len();
+
+        for item in items {
+            self.event.notify(item);
+        }
+
+        count
+    }
+}
+
+impl<T: Send + 'static> EventLoop<'static, T> {
+    /// Run an EventLoop on a dedicated worker thread. `setup` is called on the worker to subscribe to the loop,
+    /// so subscribers don't have to be [Send]. The thread exits after [shutdown](EventSender::shutdown) once the queue is drained.
+    pub fn spawn(capacity: usize, policy: BackpressurePolicy, setup: impl FnOnce(&EventLoop<'static, T>) + Send + 'static) -> (EventSender<T>, JoinHandle<()>) {
+        let queue = Arc::new(Queue::new(capacity, policy));
+        let sender = EventSender { queue: queue.clone() };
+
+        let handle = std::thread::spawn(move || {
+            let event_loop = EventLoop::with_queue(queue);
+
+            setup(&event_loop);
+            event_loop.run();
+        });
+
+        (sender, handle)
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::sync::{Arc, RwLock};
 
 type CallbackType<'a, T> = Arc<dyn Fn(&T) + 'a>;
@@ -20,4 +21,59 @@ impl<'a, T> Event<'a, T> {
             subscriber(&value);
         })
     }
+}
+
+/// An [Event] which remembers the last payloads it was notified with and replays them to every new subscriber,
+/// so subscribing late doesn't mean missing what already happened.
+pub struct ReplayEvent<'a, T> {
+    event: Event<'a, T>,
+    buffer: RwLock<VecDeque<T>>,
+    capacity: usize,
+}
+
+impl<'a, T: Clone> ReplayEvent<'a, T> {
+    /// Create a new ReplayEvent which replays up to `capacity` of the latest payloads.
+    pub fn new(capacity: usize) -> Self {
+        Self { event: Event::new(RwLock::new(Vec::new())), buffer: RwLock::new(VecDeque::with_capacity(capacity)), capacity }
+    }
+
+    /// Create a ReplayEvent which only replays the latest payload.
+    pub fn sticky() -> Self {
+        Self::new(1)
+    }
+
+    /// Subscribe to the event. The callback is immediately called with every buffered payload, oldest first.
+    ///
+    /// Replay and registration happen under the buffer lock, which [notify](ReplayEvent::notify) holds while delivering,
+    /// so every payload is received exactly once, either replayed or live, and in order. As with [Event], callbacks
+    /// must not call back into the same ReplayEvent.
+    pub fn subscribe(&self, callback: impl Fn(&T) + 'a) {
+        let buffer = self.buffer.read().unwrap();
+
+        buffer.iter().for_each(&callback);
+        self.event.subscribe(callback);
+    }
+
+    pub fn notify(&self, value: T) {
+        let mut buffer = self.buffer.write().unwrap();
+
+        if self.capacity > 0 {
+            if buffer.len() == self.capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(value.clone());
+        }
+
+        self.event.notify(value);
+    }
+
+    /// The latest payload, if there is one buffered.
+    pub fn latest(&self) -> Option<T> {
+        self.buffer.read().unwrap().back().cloned()
+    }
+
+    /// Forget every buffered payload. Subscribers from now on only receive new payloads.
+    pub fn clear(&self) {
+        self.buffer.write().unwrap().clear();
+    }
 }
\ No newline at end of file
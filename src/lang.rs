@@ -0,0 +1,209 @@
+use std::fmt::{Display, Formatter};
+use indexmap::IndexMap;
+use crate::sjson::{SJsonValue, ToSJson};
+
+/// The locale generated text is written in when no other one is given.
+pub const DEFAULT_LOCALE: &str = "en_US";
+
+/// A Bedrock `.lang` file: `key=text` lines, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LangFile {
+    pub entries: IndexMap<String, String>,
+}
+
+/// A line of a `.lang` file which couldn't be read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LangError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for LangError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for LangError {}
+
+impl LangFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a `.lang` file. Blank lines and `##` comments are skipped, including comments after a tab at the end of a line.
+    /// ```rust
+    /// use eo::lang::LangFile;
+    ///
+    /// let file = LangFile::parse("## Items\nitem.eo:sword.name=Sword\t## the basic one\n").unwrap();
+    /// assert_eq!(file.get("item.eo:sword.name"), Some("Sword"));
+    /// ```
+    pub fn parse(source: &str) -> Result<LangFile, LangError> {
+        let mut file = LangFile::new();
+
+        for (index, line) in source.trim_start_matches('\u{feff}').lines().enumerate() {
+            let line = line.split_once("\t##").map_or(line, |(line, _)| line).trim_end();
+            if line.trim_start().is_empty() || line.trim_start().starts_with("##") {
+                continue;
+            }
+
+            let error = |message: &str| LangError { line: index + 1, message: message.to_string() };
+            let (key, text) = line.split_once('=').ok_or_else(|| error("expected `key=text`"))?;
+
+            if key.trim().is_empty() {
+                return Err(error("the key is empty"));
+            }
+
+            file.entries.insert(key.trim().to_string(), text.to_string());
+        }
+
+        Ok(file)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, text: impl Into<String>) {
+        self.entries.insert(key.into(), text.into());
+    }
+
+    /// Add the entries of `other`, replacing the text of keys which are already present.
+    pub fn merge(&mut self, other: LangFile) {
+        self.entries.extend(other.entries);
+    }
+}
+
+impl Display for LangFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.entries.iter().try_for_each(|(key, text)| writeln!(f, "{key}={text}"))
+    }
+}
+
+/// Text with a localization key and its translations. Written into sJSON as the key, so it can be used for values like
+/// `minecraft:display_name`, while the translations go to the `.lang` files through [Localization::insert].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Localized {
+    pub key: String,
+    /// The text for each locale.
+    pub translations: IndexMap<String, String>,
+}
+
+impl Localized {
+    /// A key with its text in the [DEFAULT_LOCALE].
+    pub fn new(key: impl Into<String>, text: impl Into<String>) -> Self {
+        Self { key: key.into(), translations: IndexMap::from([(DEFAULT_LOCALE.to_string(), text.into())]) }
+    }
+
+    pub fn with(mut self, locale: impl Into<String>, text: impl Into<String>) -> Self {
+        self.translations.insert(locale.into(), text.into());
+        self
+    }
+}
+
+impl ToSJson for Localized {
+    fn sjson(&self) -> SJsonValue {
+        self.key.sjson()
+    }
+}
+
+/// The translations of an add-on, as one [LangFile] per locale.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Localization {
+    files: IndexMap<String, LangFile>,
+    /// The files merged with [merge_existing](Localization::merge_existing), whose keys generated text never replaces.
+    existing: IndexMap<String, LangFile>,
+}
+
+impl Localization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a text, unless an existing file merged with [merge_existing](Localization::merge_existing) already has the key.
+    pub fn translate(&mut self, locale: impl Into<String>, key: impl Into<String>, text: impl Into<String>) {
+        let (locale, key) = (locale.into(), key.into());
+
+        if self.existing.get(&locale).is_some_and(|existing| existing.get(&key).is_some()) {
+            return;
+        }
+
+        self.files.entry(locale).or_default().insert(key, text);
+    }
+
+    pub fn insert(&mut self, localized: &Localized) {
+        for (locale, text) in &localized.translations {
+            self.translate(locale.clone(), localized.key.clone(), text.clone());
+        }
+    }
+
+    /// Collect the display names of an item, block or entity document.
+    ///
+    /// The name is written as a `display_name` in the description, either as text in the [DEFAULT_LOCALE] or as an
+    /// object of locales, and stored under the key Bedrock looks up: `item.<identifier>.name`, `tile.<identifier>.name`
+    /// or `entity.<identifier>.name`. `display_name` isn't valid in Bedrock, so it is removed from the document.
+    /// Anything but text or an object of texts is left in place.
+    pub fn collect(&mut self, document: &mut SJsonValue) {
+        for (root, prefix) in [("minecraft:item", "item"), ("minecraft:block", "tile"), ("minecraft:entity", "entity")] {
+            let Some(SJsonValue::Object(description)) = document.get_mut(&format!("{root}.description")) else {
+                continue;
+            };
+            let Some(SJsonValue::String(identifier)) = description.get("identifier") else {
+                continue;
+            };
+            let key = format!("{prefix}.{identifier}.name");
+
+            let translations: Option<Vec<(String, String)>> = match description.get("display_name") {
+                Some(SJsonValue::String(text)) => Some(vec![(DEFAULT_LOCALE.to_string(), text.clone())]),
+                Some(SJsonValue::Object(locales)) => locales.iter()
+                    .map(|(locale, text)| match text {
+                        SJsonValue::String(text) => Some((locale.clone(), text.clone())),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => None,
+            };
+
+            if let Some(translations) = translations {
+                description.shift_remove("display_name");
+                for (locale, text) in translations {
+                    self.translate(locale, key.clone(), text);
+                }
+            }
+        }
+    }
+
+    /// Merge an existing `.lang` file, like one edited by a translator. Its texts win over the generated ones, whether
+    /// they were added before or after it, and keys which are only in the existing file are kept.
+    pub fn merge_existing(&mut self, locale: impl Into<String>, existing: LangFile) {
+        let locale = locale.into();
+
+        self.files.entry(locale.clone()).or_default().merge(existing.clone());
+        self.existing.entry(locale).or_default().merge(existing);
+    }
+
+    pub fn file(&self, locale: &str) -> Option<&LangFile> {
+        self.files.get(locale)
+    }
+
+    /// Every locale with its file, in the order they were first used.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &LangFile)> {
+        self.files.iter().map(|(locale, file)| (locale.as_str(), file))
+    }
+
+    /// The keys of the [DEFAULT_LOCALE] which `locale` has no translation for.
+    pub fn missing(&self, locale: &str) -> Vec<&str> {
+        let Some(default) = self.files.get(DEFAULT_LOCALE) else {
+            return vec![];
+        };
+
+        default.entries.keys()
+            .filter(|key| self.files.get(locale).is_none_or(|file| !file.entries.contains_key(*key)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
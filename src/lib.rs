@@ -1,7 +1,16 @@
+extern crate self as eo;
+
+pub mod bedrock;
+pub mod lang;
 pub mod notifiers;
+pub mod pack;
 pub mod logger;
 pub mod events;
+pub mod event_loop;
+pub mod recorder;
 pub mod sjson;
+pub mod state_machine;
+pub mod timers;
 
 pub use macros::event;
 pub use macros::event_init;
@@ -10,19 +19,19 @@ pub use macros::notifier;
 pub use macros::reactive_value;
 pub use macros::sjson;
 pub use macros::sjson_value;
+pub use macros::state_machine;
 
 #[cfg(test)]
 mod tests {
     use colored::Colorize;
-    extern crate self as eo;
 
     use crate::logger::EoLogger;
     use eo::notifier;
     use macros::{event_init, infix, reactive_value, sjson};
     use std::sync::RwLock;
-    use crate::sjson::{SJsonElement, SJsonValue, ToSJson};
 
     #[test]
+    #[allow(unused_variables)]
     fn test() {
         log::set_logger(&EoLogger).unwrap();
         log::set_max_level(log::LevelFilter::Debug);
@@ -80,4 +89,1108 @@ mod tests {
             event notify 10
         }
     }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum DoorState { Closed, Opened, Locked }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum DoorEvent { Open, Close, Lock }
+
+    #[test]
+    fn state_machine() {
+        use crate::state_machine::RejectionReason;
+        use macros::state_machine;
+        use std::cell::RefCell;
+
+        let log = RefCell::new(Vec::<String>::new());
+        let rejections = RefCell::new(Vec::new());
+        let has_key = false;
+
+        state_machine! {
+            door: DoorState, DoorEvent = DoorState::Closed;
+            DoorState::Closed => DoorState::Opened on DoorEvent::Open;
+            DoorState::Opened => DoorState::Closed on DoorEvent::Close;
+            DoorState::Closed => DoorState::Locked on DoorEvent::Lock if |_, _| has_key;
+        }
+
+        door.on_exit(DoorState::Closed, |to, _| log.borrow_mut().push(format!("exit closed -> {to:?}")));
+        door.on_enter(DoorState::Opened, |from, _| log.borrow_mut().push(format!("enter opened <- {from:?}")));
+        door.rejected().subscribe(|rejection| rejections.borrow_mut().push(rejection.reason.clone()));
+
+        assert!(!door.fire(DoorEvent::Lock));
+        assert!(door.fire(DoorEvent::Open));
+        assert!(!door.fire(DoorEvent::Open));
+        assert!(door.fire(DoorEvent::Close));
+
+        assert_eq!(door.state(), DoorState::Closed);
+        assert_eq!(*log.borrow(), vec!["exit closed -> Opened", "enter opened <- Closed"]);
+        assert_eq!(*rejections.borrow(), vec![RejectionReason::Guarded, RejectionReason::Undeclared]);
+
+        let reentrant: &'static _ = Box::leak(Box::new(crate::state_machine::StateMachine::new(DoorState::Closed)));
+        reentrant.add_guarded_transition(DoorState::Closed, DoorEvent::Open, DoorState::Opened, move |_, _| {
+            reentrant.add_transition(DoorState::Opened, DoorEvent::Close, DoorState::Closed);
+            true
+        });
+
+        assert!(reentrant.fire(DoorEvent::Open));
+        assert!(reentrant.fire(DoorEvent::Close));
+    }
+
+    #[test]
+    fn timers() {
+        use crate::events::Event;
+        use crate::timers::{Clock, ManualClock, Scheduler};
+        use std::cell::RefCell;
+        use std::time::Duration;
+
+        let ticks = &RefCell::new(Vec::new());
+        let fired = RefCell::new(0);
+        let scheduler = Scheduler::new(ManualClock::new());
+        let start = scheduler.clock().now();
+
+        let interval = Event::interval(&scheduler, Duration::from_millis(50));
+        let timeout = Event::after(&scheduler, Duration::from_millis(120));
+
+        interval.subscribe(move |instant| ticks.borrow_mut().push(*instant - start));
+        timeout.subscribe(|_| *fired.borrow_mut() += 1);
+
+        scheduler.clock().advance(Duration::from_millis(110));
+        assert_eq!(scheduler.tick(), 2);
+        assert_eq!(*ticks.borrow(), vec![Duration::from_millis(50), Duration::from_millis(100)]);
+
+        scheduler.clock().advance(Duration::from_millis(40));
+        interval.cancel();
+        assert_eq!(scheduler.tick(), 1);
+        assert_eq!(*fired.borrow(), 1);
+        assert_eq!(ticks.borrow().len(), 2);
+        assert_eq!(scheduler.next_deadline(), None);
+    }
+
+    #[test]
+    fn event_loop() {
+        use crate::event_loop::{BackpressurePolicy, DispatchError, EventLoop};
+        use std::cell::RefCell;
+        use std::sync::{Arc, Mutex};
+
+        let received = RefCell::new(Vec::new());
+        let event_loop = EventLoop::new(2, BackpressurePolicy::DropOldest);
+        event_loop.subscribe(|x: &i32| received.borrow_mut().push(*x));
+
+        let sender = event_loop.sender();
+        std::thread::spawn(move || {
+            for i in 0..3 {
+                sender.notify(i).unwrap();
+            }
+        }).join().unwrap();
+
+        assert!(received.borrow().is_empty());
+        assert_eq!(event_loop.run_pending(), 2);
+        assert_eq!(*received.borrow(), vec![1, 2]);
+
+        event_loop.shutdown();
+        assert!(matches!(event_loop.notify(3), Err(DispatchError::Closed(3))));
+
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let worker_collected = collected.clone();
+        let (sender, worker) = EventLoop::spawn(1, BackpressurePolicy::Block, move |event_loop| {
+            event_loop.subscribe(move |x: &i32| worker_collected.lock().unwrap().push(*x));
+        });
+
+        for i in 0..10 {
+            sender.notify(i).unwrap();
+        }
+        sender.shutdown();
+        worker.join().unwrap();
+
+        assert_eq!(*collected.lock().unwrap(), (0..10).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn replay_event() {
+        use crate::events::ReplayEvent;
+        use std::cell::RefCell;
+
+        let early = RefCell::new(Vec::new());
+        let late = RefCell::new(Vec::new());
+        let sticky = RefCell::new(Vec::new());
+
+        let event = ReplayEvent::new(2);
+        let loaded = ReplayEvent::sticky();
+
+        event.subscribe(|x: &i32| early.borrow_mut().push(*x));
+        for i in 1..=3 {
+            event.notify(i);
+            loaded.notify(i);
+        }
+
+        event.subscribe(|x| late.borrow_mut().push(*x));
+        loaded.subscribe(|x| sticky.borrow_mut().push(*x));
+        event.notify(4);
+
+        assert_eq!(*early.borrow(), vec![1, 2, 3, 4]);
+        assert_eq!(*late.borrow(), vec![2, 3, 4]);
+        assert_eq!(*sticky.borrow(), vec![3]);
+        assert_eq!(loaded.latest(), Some(3));
+    }
+
+    #[test]
+    fn event_recorder() {
+        use crate::events::Event;
+        use crate::notifiers::Notifier;
+        use crate::recorder::{EventRecorder, Recording};
+        use std::cell::RefCell;
+
+        let recorder = EventRecorder::new();
+        let event = event_init!(i32);
+        let notifier = Notifier::new(0);
+
+        recorder.attach(&event);
+        recorder.attach_notifier(&notifier);
+
+        event.notify(1);
+        notifier.set(2);
+        notifier.set(2);
+
+        let json = recorder.recording().to_json().unwrap();
+        let recording = Recording::<i32>::from_json(&json).unwrap();
+        assert_eq!(recording, recorder.recording());
+        assert_eq!(recording.values().copied().collect::<Vec<i32>>(), vec![1, 2, 2]);
+
+        let replayed = RefCell::new(Vec::new());
+        let fresh: Event<i32> = event_init!(i32);
+        fresh.subscribe(|x| replayed.borrow_mut().push(*x));
+        recording.replay(&fresh);
+        assert_eq!(*replayed.borrow(), vec![1, 2, 2]);
+
+        let fresh_notifier = Notifier::new(0);
+        recording.replay_notifier(&fresh_notifier);
+        assert_eq!(fresh_notifier.get(), 2);
+    }
+
+    #[test]
+    fn sjson_parse() {
+        use crate::sjson::SJsonValue;
+
+        let source = r#"{
+            "format_version": "1.20.0",
+            "minecraft:item": {
+                "components": { "minecraft:damage": { "value": 7 } },
+                "tags": ["sword", "tool\u00e9"],
+                "stackable": false
+            }
+        }"#;
+
+        let value = SJsonValue::parse(source).unwrap();
+        let reparsed = SJsonValue::parse(&serde_json::to_string(&value).unwrap()).unwrap();
+        let deserialized: SJsonValue = serde_json::from_str(source).unwrap();
+
+        assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::to_value(&reparsed).unwrap());
+        assert_eq!(serde_json::to_value(&value).unwrap(), serde_json::to_value(&deserialized).unwrap());
+
+        let error = SJsonValue::parse("{\n  \"a\": tru\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 8));
+
+        let error = "[1, 2,]".parse::<SJsonValue>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 7));
+
+        let error = SJsonValue::parse(&"[".repeat(100_000)).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 129));
+        assert!(SJsonValue::parse(&format!("{}{}", "[".repeat(128), "]".repeat(128))).is_ok());
+        assert!(SJsonValue::parse(&"{\"a\":".repeat(200)).is_err());
+    }
+
+    #[test]
+    fn sjson_null() {
+        use crate::sjson::SJsonValue;
+        use macros::sjson_value;
+
+        let some: Option<i32> = Some(3);
+        let none: Option<i32> = None;
+
+        let x = sjson! {
+            missing = null,
+            some = $some,
+            none = $none,
+            list [null, 1]
+        };
+
+        let value = serde_json::to_value(SJsonValue::Object(x.into())).unwrap();
+        assert_eq!(value, serde_json::json!({ "missing": null, "some": 3, "none": null, "list": [null, 1] }));
+
+        assert!(matches!(sjson_value!(null), SJsonValue::Null));
+        assert!(matches!(SJsonValue::parse("null"), Ok(SJsonValue::Null)));
+        assert!(matches!(serde_json::from_str::<SJsonValue>("[null]").unwrap(), SJsonValue::Array(v) if matches!(v[0], SJsonValue::Null)));
+    }
+
+    #[test]
+    fn sjson_ordering() {
+        use crate::sjson::SJsonValue;
+
+        let x = sjson! {
+            zeta = 1,
+            alpha {
+                yes = true,
+                no = false
+            },
+            mid = "m"
+        };
+
+        let ordered = serde_json::to_string(&SJsonValue::Object(x.clone().into())).unwrap();
+        assert_eq!(ordered, r#"{"zeta":1,"alpha":{"yes":true,"no":false},"mid":"m"}"#);
+
+        let sorted = x.serialize_sorted().unwrap().split_whitespace().collect::<String>();
+        assert_eq!(sorted, r#"{"alpha":{"no":false,"yes":true},"mid":"m","zeta":1}"#);
+
+        let parsed = SJsonValue::parse(r#"{"b": 1, "a": 2}"#).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), r#"{"b":1,"a":2}"#);
+    }
+
+    #[test]
+    fn sjson_numbers() {
+        use crate::sjson::{SJsonNumber, SJsonValue};
+
+        let big = u64::MAX;
+        let precise = i64::MAX - 1;
+        let small = 3u8;
+
+        let x = sjson! {
+            damage = 7,
+            negative = -7,
+            ratio = 0.5,
+            whole = 7.0,
+            big = $big,
+            precise = $precise,
+            small = $small
+        };
+
+        let serialized = serde_json::to_string(&SJsonValue::Object(x.into())).unwrap();
+        assert_eq!(serialized, format!(
+            r#"{{"damage":7,"negative":-7,"ratio":0.5,"whole":7.0,"big":{},"precise":{},"small":3}}"#, u64::MAX, i64::MAX - 1
+        ));
+
+        let parsed = SJsonValue::parse(&serialized).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), serialized);
+
+        let SJsonValue::Object(object) = parsed else { panic!() };
+        assert!(matches!(object["big"], SJsonValue::Number(SJsonNumber::UInt(u64::MAX))));
+        assert!(matches!(object["damage"], SJsonValue::Number(n) if n == SJsonNumber::from(7u8)));
+    }
+
+    mod components {
+        pub const MAX_DAMAGE: i32 = 12;
+    }
+
+    struct Item {
+        name: String,
+        stats: (i32, bool),
+    }
+
+    #[test]
+    fn sjson_interpolation() {
+        use crate::sjson::SJsonValue;
+
+        let item = Item { name: "sword".to_string(), stats: (7, true) };
+        let bonus = 3;
+        let tags = ["a", "b"];
+
+        let x = sjson! {
+            name = $item.name,
+            damage = $item.stats.0,
+            durable = $item.stats.1,
+            max = $components::MAX_DAMAGE,
+            total = $(item.stats.0 + bonus),
+            label = $(format!("{}_{}", item.name, bonus)),
+            tags = $(tags.iter().map(|t| t.to_uppercase()).collect::<Vec<String>>().join(","))
+        };
+
+        let value = serde_json::to_value(SJsonValue::Object(x.into())).unwrap();
+        assert_eq!(value, serde_json::json!({
+            "name": "sword", "damage": 7, "durable": true, "max": 12, "total": 10, "label": "sword_3", "tags": "A,B"
+        }));
+    }
+
+    #[test]
+    fn sjson_control_flow() {
+        use crate::sjson::SJsonValue;
+        use macros::sjson_value;
+
+        let glowing = true;
+        let stackable = false;
+        let items = [("apple", 1), ("pear", 2)];
+        let base = sjson_value!({ durability = 10, tier = "iron" });
+        let extra = sjson_value!(["x", "y"]);
+
+        let x = sjson! {
+            if $glowing {
+                glint = true
+            }
+            if $stackable {
+                max_stack_size = 64
+            } else {
+                max_stack_size = 1
+            }
+            for $(name, _) in $(items.iter()) {
+                last = $name
+            },
+            stats {
+                ..$base,
+                tier = "diamond",
+                empty {}
+            },
+            tags [
+                "first",
+                for $(name, _) in $(items.iter()) { $name },
+                if $stackable { "stackable" },
+                ..$extra
+            ]
+        };
+
+        let value = serde_json::to_string(&SJsonValue::Object(x.into())).unwrap();
+        assert_eq!(value, concat!(
+            r#"{"glint":true,"max_stack_size":1,"last":"pear","#,
+            r#""stats":{"durability":10,"tier":"diamond","empty":{}},"tags":["first","apple","pear","x","y"]}"#
+        ));
+
+        let missing: Option<Vec<i32>> = None;
+        let tiers = std::collections::HashMap::from([("b".to_string(), 2), ("a".to_string(), 1)]);
+        let y = sjson! {
+            ..$tiers,
+            ..$(SJsonValue::Null),
+            list [..$missing, ..$(vec![1, 2])]
+        };
+
+        let value = serde_json::to_string(&SJsonValue::Object(y.into())).unwrap();
+        assert_eq!(value, r#"{"a":1,"b":2,"list":[1,2]}"#);
+
+        let spread_object = std::panic::catch_unwind(|| sjson! { list [..$base] }).unwrap_err();
+        assert_eq!(spread_object.downcast_ref::<String>().unwrap(), "Only arrays can be spread into an sJSON array, found object.");
+    }
+
+    #[test]
+    fn sjson_keys() {
+        use crate::sjson::SJsonValue;
+
+        let namespace = "eo";
+        let versions = ["1.20.0", "1.21.0"];
+
+        let x = sjson! {
+            "format_version" = "1.20.0",
+            "minecraft:type" = "sword",
+            "has space-and/slash" = true,
+            "1.20.0" { "type" = 1 },
+            [$(format!("{namespace}:custom"))] = 2,
+            [$namespace] [1, 2],
+            supported {
+                for $version in $versions {
+                    [$version] = true
+                }
+            }
+        };
+
+        let value = serde_json::to_string(&SJsonValue::Object(x.into())).unwrap();
+        assert_eq!(value, concat!(
+            r#"{"format_version":"1.20.0","minecraft:type":"sword","has space-and/slash":true,"1.20.0":{"type":1},"#,
+            r#""eo:custom":2,"eo":[1,2],"supported":{"1.20.0":true,"1.21.0":true}}"#
+        ));
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson, crate::sjson::HasSJsonIdent)]
+    #[sjson(ident = "minecraft:damage", rename_all = "snake_case")]
+    struct DamageComponent {
+        #[sjson(rename = "value")]
+        amount: i32,
+        damage_type: Option<String>,
+        #[sjson(skip)]
+        _cached: bool,
+        #[sjson(flatten)]
+        extra: Durability,
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    struct Durability {
+        max_durability: u32,
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    struct Position(i32, i32, #[sjson(skip)] i32);
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    #[sjson(rename_all = "snake_case")]
+    enum Trigger {
+        OnUse,
+        Event(String),
+        Summon { entity: String, count: u8 },
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    #[sjson(tag = "type", rename_all = "lowercase")]
+    enum Shape {
+        Cube { size: f64 },
+        Circle(Radius),
+        Unit,
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    struct Radius {
+        radius: f64,
+    }
+
+    #[derive(crate::sjson::ToSJson)]
+    #[sjson(tag = "type")]
+    enum Scalar {
+        Length(f64),
+    }
+
+    #[derive(crate::sjson::ToSJson)]
+    struct Flattened {
+        #[sjson(flatten)]
+        count: u8,
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::ToSJson, crate::sjson::FromSJson)]
+    #[sjson(tag = "t", content = "c")]
+    enum Adjacent {
+        Value(Vec<String>),
+    }
+
+    #[derive(crate::sjson::ToSJson)]
+    #[sjson(untagged)]
+    enum Either {
+        Number(u8),
+        Text(&'static str),
+    }
+
+    #[test]
+    fn sjson_derive() {
+        use crate::sjson::{HasSJsonIdent, SJsonValue, ToSJson};
+
+        let to_string = |value: SJsonValue| serde_json::to_string(&value).unwrap();
+
+        let damage = DamageComponent { amount: 7, damage_type: None, _cached: true, extra: Durability { max_durability: 100 } };
+        assert_eq!(damage.sjson_ident(), "minecraft:damage");
+        assert_eq!(to_string(damage.sjson()), r#"{"value":7,"damage_type":null,"max_durability":100}"#);
+
+        let position = Position(1, 2, 3);
+        assert_eq!(to_string(position.sjson()), "[1,2]");
+        assert_eq!(position.2, 3);
+        assert_eq!(to_string(Trigger::OnUse.sjson()), r#""on_use""#);
+        assert_eq!(to_string(Trigger::Event("hit".to_string()).sjson()), r#"{"event":"hit"}"#);
+        assert_eq!(to_string(Trigger::Summon { entity: "pig".to_string(), count: 2 }.sjson()), r#"{"summon":{"entity":"pig","count":2}}"#);
+        assert_eq!(to_string(Shape::Cube { size: 0.5 }.sjson()), r#"{"type":"cube","size":0.5}"#);
+        assert_eq!(to_string(Shape::Unit.sjson()), r#"{"type":"unit"}"#);
+        assert_eq!(to_string(Shape::Circle(Radius { radius: 2.5 }).sjson()), r#"{"type":"circle","radius":2.5}"#);
+        assert_eq!(to_string(Adjacent::Value(vec!["a".to_string()]).sjson()), r#"{"t":"Value","c":["a"]}"#);
+        assert_eq!(to_string(vec![Either::Number(1), Either::Text("a")].sjson()), r#"[1,"a"]"#);
+
+        // Content which isn't an object can't be merged next to the tag or into the parent, so it isn't silently dropped.
+        let scalar = std::panic::catch_unwind(|| Scalar::Length(2.5).sjson()).unwrap_err();
+        assert_eq!(scalar.downcast_ref::<String>().unwrap(), "The internally tagged variant `Scalar::Length` must contain an object, found number.");
+        let flattened = std::panic::catch_unwind(|| Flattened { count: 1 }.sjson()).unwrap_err();
+        assert_eq!(flattened.downcast_ref::<String>().unwrap(), "The flattened field `Flattened.count` must be an object, found number.");
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::FromSJson)]
+    #[sjson(untagged)]
+    enum Amount {
+        Exact(u8),
+        Range { min: u8, max: u8 },
+    }
+
+    #[derive(Debug, PartialEq, crate::sjson::FromSJson)]
+    struct Weapon {
+        #[sjson(rename = "minecraft:damage")]
+        damage: DamageComponent,
+        tags: Vec<String>,
+        counts: std::collections::HashMap<String, Amount>,
+    }
+
+    #[test]
+    fn sjson_from() {
+        use crate::sjson::{FromSJson, SJsonValue, ToSJson};
+
+        let parse = |source: &str| SJsonValue::parse(source).unwrap();
+
+        assert_eq!(i32::from_sjson(&parse("-3")), Ok(-3));
+        assert_eq!(u8::from_sjson(&parse("300")).unwrap_err().to_string(), "300 does not fit into u8");
+        assert_eq!(Option::<String>::from_sjson(&parse("null")), Ok(None));
+        assert_eq!(Vec::<bool>::from_sjson(&parse("[true, 1]")).unwrap_err().to_string(), "[1]: expected boolean, found number");
+
+        let damage = DamageComponent { amount: 7, damage_type: Some("fire".to_string()), _cached: true, extra: Durability { max_durability: 100 } };
+        let read = DamageComponent::from_sjson(&damage.sjson()).unwrap();
+        assert_eq!(read.amount, 7);
+        assert_eq!(read.damage_type.as_deref(), Some("fire"));
+        assert!(!read._cached);
+        assert_eq!(read.extra, Durability { max_durability: 100 });
+
+        assert_eq!(Position::from_sjson(&parse("[1, 2]")), Ok(Position(1, 2, 0)));
+        assert_eq!(Trigger::from_sjson(&parse(r#""on_use""#)), Ok(Trigger::OnUse));
+        assert_eq!(Trigger::from_sjson(&parse(r#"{"summon": {"entity": "pig", "count": 2}}"#)), Ok(Trigger::Summon { entity: "pig".to_string(), count: 2 }));
+        assert_eq!(Trigger::from_sjson(&parse(r#""jump""#)).unwrap_err().to_string(), "unknown variant `jump`, expected one of `on_use`, `event`, `summon`");
+        assert_eq!(Shape::from_sjson(&parse(r#"{"type": "cube", "size": 0.5}"#)), Ok(Shape::Cube { size: 0.5 }));
+        assert_eq!(Shape::from_sjson(&parse(r#"{"type": "unit"}"#)), Ok(Shape::Unit));
+        let circle = Shape::Circle(Radius { radius: 2.5 });
+        assert_eq!(Shape::from_sjson(&circle.sjson()), Ok(circle));
+        assert_eq!(Adjacent::from_sjson(&parse(r#"{"t": "Value", "c": ["a"]}"#)), Ok(Adjacent::Value(vec!["a".to_string()])));
+        assert_eq!(Amount::from_sjson(&parse(r#"{"min": 1, "max": 3}"#)), Ok(Amount::Range { min: 1, max: 3 }));
+
+        let item = Weapon::from_sjson(&parse(r#"{
+            "minecraft:damage": { "value": 2, "max_durability": 10 },
+            "tags": ["sword"],
+            "counts": { "drop": 1 }
+        }"#)).unwrap();
+        assert_eq!(item.damage.amount, 2);
+        assert_eq!(item.counts["drop"], Amount::Exact(1));
+
+        let error = Weapon::from_sjson(&parse(r#"{ "minecraft:damage": { "value": "high", "max_durability": 10 }, "tags": [], "counts": {} }"#)).unwrap_err();
+        assert_eq!(error.to_string(), "minecraft:damage.value: expected number, found string");
+
+        let error = Weapon::from_sjson(&parse(r#"{ "minecraft:damage": { "value": 1 }, "tags": [], "counts": {} }"#)).unwrap_err();
+        assert_eq!(error.to_string(), "minecraft:damage: missing field `max_durability`");
+    }
+
+    #[test]
+    fn sjson_direct_inclusion() {
+        use crate::sjson::SJsonValue;
+
+        let damage = DamageComponent { amount: 3, damage_type: None, _cached: false, extra: Durability { max_durability: 5 } };
+
+        let x = sjson! {
+            minecraft:damage = "overwritten",
+            format_version = "1.21.0",
+            components {
+                : damage,
+                minecraft:icon = "sword"
+            },
+            : damage
+        };
+
+        let serialized = serde_json::to_string(&SJsonValue::Object(x.to_object())).unwrap();
+        assert_eq!(serialized, concat!(
+            r#"{"minecraft:damage":{"value":3,"damage_type":null,"max_durability":5},"format_version":"1.21.0","#,
+            r#""components":{"minecraft:damage":{"value":3,"damage_type":null,"max_durability":5},"minecraft:icon":"sword"}}"#
+        ));
+        assert_eq!(SJsonValue::parse(&x.serialize().unwrap()).unwrap().type_name(), "object");
+
+        // User variables named like the locals of an inclusion are still in scope after it.
+        let id = String::from("eo:sword");
+        let params = 2;
+        let y = sjson! {
+            : damage,
+            identifier = $id,
+            components {
+                : damage,
+                count = $params,
+                name = $id
+            }
+        };
+        let y = SJsonValue::from(y);
+        assert_eq!(y["identifier"].to_string(), r#""eo:sword""#);
+        assert_eq!(y["components"]["count"].to_string(), "2");
+        assert_eq!(sjson! {}.serialize().unwrap(), "{}");
+    }
+
+    #[test]
+    fn sjson_writer() {
+        use crate::sjson::{Indent, SJsonValue, SerializeOptions};
+
+        let x = sjson! {
+            b = 1,
+            a [true, null]
+        };
+
+        assert_eq!(x.to_string_compact().unwrap(), r#"{"b":1,"a":[true,null]}"#);
+        assert_eq!(x.to_string(), r#"{"b":1,"a":[true,null]}"#);
+        assert_eq!(format!("{x:#}"), x.serialize().unwrap());
+        assert_eq!(x.serialize().unwrap(), "{\n  \"b\": 1,\n  \"a\": [\n    true,\n    null\n  ]\n}");
+        assert_eq!(format!("{:#}", sjson! { nested { objects = true } }), "{\n  \"nested\": {\n    \"objects\": true\n  }\n}");
+        assert_eq!(
+            x.to_string_with(SerializeOptions::default().indent(Indent::Tabs).sort_keys()).unwrap(),
+            "{\n\t\"a\": [\n\t\ttrue,\n\t\tnull\n\t],\n\t\"b\": 1\n}"
+        );
+
+        let mut buffer = Vec::new();
+        x.to_writer(&mut buffer, SerializeOptions::default().indent(Indent::Spaces(4))).unwrap();
+        assert!(String::from_utf8(buffer).unwrap().starts_with("{\n    \"b\": 1"));
+
+        let value = SJsonValue::parse(r#"{ "text": "line\nbreak", "n": 1.5 }"#).unwrap();
+        assert_eq!(value.to_string(), r#"{"text":"line\nbreak","n":1.5}"#);
+
+        struct FailingWriter;
+
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        assert!(value.to_writer(FailingWriter, SerializeOptions::compact()).unwrap_err().is_io());
+    }
+
+    #[test]
+    fn sjson_query() {
+        use crate::sjson::{Query, QuerySegment, SJsonValue};
+
+        let mut value = SJsonValue::parse(r#"{
+            "format_version": "1.21.0",
+            "components": {
+                "minecraft:damage": { "value": 7 },
+                "minecraft:durability": { "value": 100 },
+                "minecraft:icon": "sword"
+            },
+            "supported": { "1.20.0": true },
+            "a/b": [10, { "~c": 20 }]
+        }"#).unwrap();
+
+        assert_eq!(value.get("components.minecraft:damage.value").map(ToString::to_string).as_deref(), Some("7"));
+        assert_eq!(value.get(r#"supported["1.20.0"]"#).map(ToString::to_string).as_deref(), Some("true"));
+        assert_eq!(value.get("a/b[1].~c").map(ToString::to_string).as_deref(), Some("20"));
+        assert!(value.get("components.missing").is_none());
+        assert!(value.get("components.*").is_none());
+        assert!(value.get("components..value").is_none());
+
+        assert_eq!(value.pointer("/a~1b/1/~0c").map(ToString::to_string).as_deref(), Some("20"));
+        assert!(value.pointer("/a~1b/01").is_none());
+        assert!(value.pointer("a~1b").is_none());
+        assert_eq!(value.pointer("").map(SJsonValue::type_name), Some("object"));
+
+        let values: Vec<String> = value.query("components.*.value").unwrap().map(ToString::to_string).collect();
+        assert_eq!(values, ["7", "100"]);
+        assert_eq!(value.query("a/b[*]").unwrap().count(), 2);
+        assert_eq!(value.query("components[0").unwrap_err().to_string(), "expected `]` at position 12");
+        assert_eq!(
+            Query::parse(r#"a[2]["x.y"].*"#).unwrap().segments,
+            [QuerySegment::Key("a".to_string()), QuerySegment::Index(2), QuerySegment::Key("x.y".to_string()), QuerySegment::Wildcard]
+        );
+
+        assert_eq!(value["components"]["minecraft:icon"].to_string(), r#""sword""#);
+        assert_eq!(value["a/b"][5].type_name(), "null");
+
+        *value.get_mut("components.minecraft:damage.value").unwrap() = SJsonValue::Boolean(false);
+        *value.pointer_mut("/a~1b/0").unwrap() = SJsonValue::Null;
+        value["components"]["minecraft:new"]["nested"] = SJsonValue::String("built".to_string());
+        value["a/b"][1]["~c"] = SJsonValue::Number(21.into());
+
+        assert_eq!(value["components"].to_string(), concat!(
+            r#"{"minecraft:damage":{"value":false},"minecraft:durability":{"value":100},"#,
+            r#""minecraft:icon":"sword","minecraft:new":{"nested":"built"}}"#
+        ));
+        assert_eq!(value["a/b"].to_string(), r#"[null,{"~c":21}]"#);
+    }
+
+    #[test]
+    fn sjson_merge_and_patch() {
+        use crate::sjson::{FromSJson, MergeStrategy, PatchOperation, SJsonValue, ToSJson};
+
+        let parse = |source: &str| SJsonValue::parse(source).unwrap();
+
+        let base = parse(r#"{
+            "minecraft:damage": { "value": 1, "type": "melee" },
+            "events": [{ "name": "on_hit", "sound": "a" }, { "name": "on_use" }]
+        }"#);
+        let overrides = parse(r#"{
+            "minecraft:damage": { "value": 7 },
+            "events": [{ "name": "on_hit", "sound": "b" }, { "name": "on_break" }]
+        }"#);
+
+        let mut replaced = base.clone();
+        replaced.merge(overrides.clone(), &MergeStrategy::Replace);
+        assert_eq!(replaced.to_string(), r#"{"minecraft:damage":{"value":7,"type":"melee"},"events":[{"name":"on_hit","sound":"b"},{"name":"on_break"}]}"#);
+
+        let mut appended = base.clone();
+        appended.merge(overrides.clone(), &MergeStrategy::Append);
+        assert_eq!(appended["events"].query("[*].name").unwrap().count(), 4);
+
+        let mut keyed = base.clone();
+        keyed.merge(overrides, &MergeStrategy::ByKey("name".to_string()));
+        assert_eq!(keyed["events"].to_string(), r#"[{"name":"on_hit","sound":"b"},{"name":"on_use"},{"name":"on_break"}]"#);
+
+        // RFC 7386, appendix A
+        let mut document = parse(r#"{ "title": "Goodbye!", "author": { "givenName": "John", "familyName": "Doe" }, "tags": ["example", "sample"], "content": "This will be unchanged" }"#);
+        document.merge_patch(&parse(r#"{ "title": "Hello!", "phoneNumber": "+01-123-456-7890", "author": { "familyName": null }, "tags": ["example"] }"#));
+        assert_eq!(document, parse(r#"{ "title": "Hello!", "author": { "givenName": "John" }, "tags": ["example"], "content": "This will be unchanged", "phoneNumber": "+01-123-456-7890" }"#));
+
+        let mut document = parse(r#"{ "foo": "bar", "list": [1, 2, 3] }"#);
+        let patch = Vec::<PatchOperation>::from_sjson(&parse(r#"[
+            { "op": "test", "path": "/foo", "value": "bar" },
+            { "op": "test", "path": "/list", "value": [1.0, 2, 3e0] },
+            { "op": "add", "path": "/list/-", "value": 4 },
+            { "op": "add", "path": "/list/0", "value": 0 },
+            { "op": "remove", "path": "/list/1" },
+            { "op": "replace", "path": "/foo", "value": null },
+            { "op": "copy", "from": "/list", "path": "/copy" },
+            { "op": "move", "from": "/foo", "path": "/a~1b" }
+        ]"#)).unwrap();
+        document.apply_patch(&patch).unwrap();
+        assert_eq!(document.to_string(), r#"{"list":[0,2,3,4],"copy":[0,2,3,4],"a/b":null}"#);
+
+        let failing = [
+            PatchOperation::Remove { path: "/list/0".to_string() },
+            PatchOperation::Test { path: "/list/0".to_string(), value: SJsonValue::Number(0.into()) },
+        ];
+        assert_eq!(document.apply_patch(&failing).unwrap_err().to_string(), "operation 1: test failed: `/list/0` is 2, expected 0");
+        assert_eq!(document["list"].to_string(), "[0,2,3,4]");
+        assert!(document.apply_patch(&[PatchOperation::Move { from: "/list".to_string(), path: "/list/0".to_string() }]).is_err());
+
+        let from = parse(r#"{ "a": 1, "b": [1, 2, 3], "c": { "d~": true }, "gone": null }"#);
+        let to = parse(r#"{ "a": "1", "b": [1, 5], "c": { "d~": true, "e/": false }, "new": [] }"#);
+        let patch = from.diff_patch(&to);
+        assert_eq!(patch.sjson().to_string(), concat!(
+            r#"[{"op":"remove","path":"/gone"},{"op":"replace","path":"/a","value":"1"},"#,
+            r#"{"op":"replace","path":"/b/1","value":5},{"op":"remove","path":"/b/2"},"#,
+            r#"{"op":"add","path":"/c/e~1","value":false},{"op":"add","path":"/new","value":[]}]"#
+        ));
+
+        let mut patched = from.clone();
+        patched.apply_patch(&patch).unwrap();
+        assert_eq!(patched, to);
+        assert!(to.diff_patch(&to).is_empty());
+    }
+
+    #[test]
+    fn sjson_diff() {
+        use crate::sjson::{diff, render_diff, ChangeKind, PathSegment, SJsonValue};
+
+        let a = SJsonValue::parse(r#"{
+            "format_version": "1.20.0",
+            "components": { "minecraft:damage": { "value": 1 }, "minecraft:icon": "sword" },
+            "tags": ["a", "b"]
+        }"#).unwrap();
+        let b = SJsonValue::parse(r#"{
+            "format_version": "1.21.0",
+            "components": { "minecraft:damage": { "value": "high" }, "minecraft:glint": true },
+            "tags": ["a"]
+        }"#).unwrap();
+
+        let changes = diff(&a, &b);
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, [
+            r#"~ format_version: "1.20.0" -> "1.21.0""#,
+            r#"! components.minecraft:damage.value: 1 (number) -> "high" (string)"#,
+            r#"- components.minecraft:icon: "sword""#,
+            "+ components.minecraft:glint: true",
+            r#"- tags[1]: "b""#,
+        ]);
+        assert_eq!(changes[4].path, [PathSegment::Key("tags".to_string()), PathSegment::Index(1)]);
+        assert!(matches!(changes[3].kind, ChangeKind::Added(SJsonValue::Boolean(true))));
+
+        assert!(diff(&a, &a.clone()).is_empty());
+        assert_eq!(diff(&a, &SJsonValue::Null)[0].to_string().split(':').next(), Some("! (root)"));
+
+        let rendered = render_diff(&changes);
+        assert_eq!(rendered.lines().count(), 5);
+        assert!(rendered.contains("components.minecraft:glint"));
+    }
+
+    #[test]
+    fn sjson_schema() {
+        use crate::sjson::{FromSJson, Schema, SchemaType, SJsonValue};
+
+        let parse = |source: &str| SJsonValue::parse(source).unwrap();
+
+        let schema = Schema::object()
+            .property("format_version", Schema::string().pattern(r"^\d+\.\d+\.\d+$"))
+            .property("components", Schema::object()
+                .property("minecraft:damage", Schema::integer().minimum(0.0).maximum(100.0))
+                .property("minecraft:icon", Schema::string())
+                .property("minecraft:hand_equipped", Schema::boolean())
+                .deny_unknown_properties())
+            .property("tags", Schema::array(Schema::string().enumeration([SJsonValue::String("sword".to_string())])))
+            .property("glint", Schema::boolean().or_type(SchemaType::Null))
+            .required("format_version")
+            .required("components");
+
+        assert!(schema.validate(&parse(r#"{
+            "format_version": "1.21.0",
+            "components": { "minecraft:damage": 7, "minecraft:icon": "sword" },
+            "tags": ["sword"],
+            "glint": null,
+            "extra": "allowed"
+        }"#)).is_ok());
+
+        let errors = schema.validate(&parse(r#"{
+            "format_version": "1.21",
+            "components": { "minecraft:damage": 7.5, "minecraft:hand_equiped": true, "custom": 1 },
+            "tags": ["sword", "axe"],
+            "glint": 1
+        }"#)).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, [
+            r#"format_version: "1.21" does not match the pattern `^\d+\.\d+\.\d+$`"#,
+            "components.minecraft:damage: expected integer, found number",
+            "components.minecraft:hand_equiped: unknown property, did you mean `minecraft:hand_equipped`?",
+            "components.custom: unknown property",
+            r#"tags[1]: "axe" is not one of "sword""#,
+            "glint: expected boolean or null, found number",
+        ]);
+
+        let loaded = Schema::from_sjson(&parse(r#"{
+            "type": "object",
+            "required": ["minecraft:damage"],
+            "additionalProperties": false,
+            "properties": {
+                "minecraft:damage": { "type": ["integer", "null"], "minimum": 1 },
+                "minecraft:tags": { "type": "array", "items": { "enum": ["a", "b"] } }
+            }
+        }"#)).unwrap();
+        let messages: Vec<String> = loaded.validate(&parse(r#"{ "minecraft:damage": 0, "minecraft:tags": ["c"], "minecraft:tag": [] }"#))
+            .unwrap_err().iter().map(ToString::to_string).collect();
+        assert_eq!(messages, [
+            "minecraft:damage: 0 is less than the minimum of 1",
+            r#"minecraft:tags[0]: "c" is not one of "a", "b""#,
+            "minecraft:tag: unknown property, did you mean `minecraft:tags`?",
+        ]);
+        assert_eq!(loaded.validate(&parse("{}")).unwrap_err()[0].to_string(), "missing required property `minecraft:damage`");
+
+        let versions = Schema::from_sjson(&parse(r#"{ "enum": [1, 2.5] }"#)).unwrap();
+        assert!(versions.validate(&parse("1.0")).is_ok());
+        assert!(versions.validate(&parse("2")).is_err());
+
+        let invalid = Schema::from_sjson(&parse(r#"{ "properties": { "a": { "type": "text" } } }"#)).unwrap_err();
+        assert_eq!(invalid.to_string(), "properties.a.type: unknown type `text`");
+    }
+
+    #[test]
+    fn sjson_schema_macro() {
+        use crate::sjson::{FromSJson, Schema, SJsonValue};
+
+        let damage = 7;
+        let tags = ["eo:sword", "eo:weapon"];
+
+        let x = sjson!(schema = "../tests/schemas/item.json", {
+            format_version = "1.21.0",
+            minecraft:item {
+                description {
+                    identifier = "eo:sword",
+                    menu_category { category = "equipment" }
+                },
+                components {
+                    minecraft:damage = $damage,
+                    if $(damage > 5) { minecraft:hand_equipped = true },
+                    minecraft:max_stack_size = 1,
+                    minecraft:tags { tags [for $tag in $tags { $tag }] }
+                }
+            }
+        });
+
+        let schema = Schema::from_sjson(&SJsonValue::parse(include_str!("../tests/schemas/item.json")).unwrap()).unwrap();
+        assert!(schema.validate(&SJsonValue::Object(x.to_object())).is_ok());
+
+        // Numbers in an `enum` match by value, as in `Schema::validate`.
+        let tier = sjson!(schema = "../tests/schemas/tier.json", { tier = 1.0 });
+        assert_eq!(tier.to_string_compact().unwrap(), r#"{"tier":1.0}"#);
+
+        // A plain `schema` key is still an ordinary entry.
+        let y = sjson! { schema = "not a schema" };
+        assert_eq!(y.to_string_compact().unwrap(), r#"{"schema":"not a schema"}"#);
+    }
+
+    #[test]
+    fn bedrock_builders() {
+        use crate::bedrock::components::*;
+        use crate::bedrock::{Block, Category, Components, Entity, Item, MenuCategory};
+        use crate::sjson::{FromSJson, Schema, SJsonValue};
+
+        let sword = Item::new("eo:sword")
+            .menu_category(MenuCategory::new(Category::Equipment).group("minecraft:itemGroup.name.sword"))
+            .component(Damage { value: 7 })
+            .component(Icon("eo_sword".to_string()))
+            .component(MaxStackSize(1))
+            .component(HandEquipped(true));
+
+        let x = sjson! {
+            format_version = "1.21.0",
+            : sword
+        };
+        assert_eq!(x.to_string_compact().unwrap(), concat!(
+            r#"{"format_version":"1.21.0","minecraft:item":{"description":{"identifier":"eo:sword","#,
+            r#""menu_category":{"category":"equipment","group":"minecraft:itemGroup.name.sword"}},"#,
+            r#""components":{"minecraft:damage":{"value":7},"minecraft:icon":"eo_sword","minecraft:max_stack_size":1,"minecraft:hand_equipped":true}}}"#
+        ));
+        assert_eq!(SJsonValue::Object(x.to_object()), sword.document());
+
+        let schema = Schema::from_sjson(&SJsonValue::parse(include_str!("../tests/schemas/bedrock_item.json")).unwrap()).unwrap();
+        assert!(schema.validate(&sword.document()).is_ok());
+
+        let lamp = Block::new("eo:lamp")
+            .format_version("1.20.80")
+            .component(LightEmission(15))
+            .component(DestructibleByMining { seconds_to_destroy: 0.5 })
+            .component(LightEmission(10));
+        assert_eq!(lamp.document().to_string(), concat!(
+            r#"{"format_version":"1.20.80","minecraft:block":{"description":{"identifier":"eo:lamp"},"#,
+            r#""components":{"minecraft:light_emission":10,"minecraft:destructible_by_mining":{"seconds_to_destroy":0.5}}}}"#
+        ));
+
+        let golem = Entity::new("eo:golem")
+            .summonable(false)
+            .component(Health { value: 20, max: 20 })
+            .component(Physics {})
+            .component_group("eo:angry", Components::new().with(Movement { value: 0.4 }))
+            .event("eo:become_angry", crate::sjson_value!({ add { component_groups ["eo:angry"] } }));
+        let document = golem.document();
+        assert_eq!(document["minecraft:entity"]["description"].to_string(), r#"{"identifier":"eo:golem","is_spawnable":true,"is_summonable":false}"#);
+        assert_eq!(document["minecraft:entity"]["components"].to_string(), r#"{"minecraft:health":{"value":20,"max":20},"minecraft:physics":{}}"#);
+        assert_eq!(document.get("minecraft:entity.component_groups.eo:angry.minecraft:movement.value").map(ToString::to_string).as_deref(), Some("0.4"));
+        assert_eq!(document.pointer("/minecraft:entity/events/eo:become_angry/add/component_groups/0").map(ToString::to_string).as_deref(), Some(r#""eo:angry""#));
+        assert!(Entity::new("eo:dummy").document()["minecraft:entity"].get("events").is_none());
+    }
+
+    #[test]
+    fn pack_project() {
+        use std::path::PathBuf;
+        use crate::bedrock::components::{Damage, Health, LightEmission};
+        use crate::bedrock::{Block, Entity, Item};
+        use crate::pack::{PackError, Project};
+        use crate::sjson::{SJsonValue, SerializeOptions};
+
+        let mut project = Project::new("eo_test", "Generated by eo");
+        project.options = SerializeOptions::compact();
+        project
+            .item(&Item::new("eo:sword").component(Damage { value: 7 }))
+            .item(&Item::new("other:sword"))
+            .block(&Block::new("eo:lamp").component(LightEmission(15)))
+            .entity(&Entity::new("eo:golem").component(Health { value: 20, max: 20 }))
+            .behavior_file("loot_tables/golem.json", sjson! { pools [] })
+            .resource_file("textures/item_texture.json", sjson! { resource_pack_name = "eo_test", texture_data {} })
+            .translation("item.eo:sword.name", "Sword");
+
+        let paths: Vec<String> = project.dry_run().unwrap().iter().map(|path| path.to_string_lossy().replace('\\', "/")).collect();
+        assert_eq!(paths, [
+            "eo_test_BP/manifest.json",
+            "eo_test_BP/items/eo/sword.json",
+            "eo_test_BP/items/other/sword.json",
+            "eo_test_BP/blocks/eo/lamp.json",
+            "eo_test_BP/entities/eo/golem.json",
+            "eo_test_BP/loot_tables/golem.json",
+            "eo_test_RP/manifest.json",
+            "eo_test_RP/textures/item_texture.json",
+            "eo_test_RP/texts/languages.json",
+            "eo_test_RP/texts/en_US.lang",
+        ]);
+
+        let files = project.render().unwrap();
+        let behavior = SJsonValue::parse(&files[0].contents).unwrap();
+        let resource = SJsonValue::parse(&files[6].contents).unwrap();
+        assert_eq!(behavior["modules"][0]["type"].to_string(), r#""data""#);
+        assert_eq!(resource["modules"][0]["type"].to_string(), r#""resources""#);
+        assert_eq!(behavior["header"]["version"].to_string(), "[1,0,0]");
+        assert_eq!(behavior["dependencies"][0]["uuid"], resource["header"]["uuid"]);
+        assert_eq!(resource["dependencies"][0]["uuid"], behavior["header"]["uuid"]);
+        assert_ne!(behavior["header"]["uuid"], behavior["modules"][0]["uuid"]);
+        assert_eq!(files[9].contents, "item.eo:sword.name=Sword\n");
+
+        // The same project always gets the same UUIDs, another project different ones.
+        assert_eq!(Project::new("eo_test", "").behavior_uuids, project.behavior_uuids);
+        assert_ne!(Project::new("eo_other", "").behavior_uuids, project.behavior_uuids);
+
+        let directory = std::env::temp_dir().join(format!("eo_pack_test_{}", std::process::id()));
+        let written = project.write(&directory).unwrap();
+        assert_eq!(written.len(), 10);
+        assert_eq!(std::fs::read_to_string(directory.join("eo_test_BP/items/eo/sword.json")).unwrap(), files[1].contents);
+        assert_ne!(files[1].contents, files[2].contents);
+        assert_eq!(written[5], directory.join(PathBuf::from("eo_test_BP/loot_tables/golem.json")));
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // Nothing may end up outside the pack directories.
+        let escapes = |configure: &dyn Fn(&mut Project)| {
+            let mut project = Project::new("eo_test", "");
+            configure(&mut project);
+
+            match project.render() {
+                Err(PackError::InvalidPath(path)) => path,
+                other => panic!("expected an invalid path, got {other:?}"),
+            }
+        };
+        let absolute = std::env::temp_dir().join("eo_escaped.json");
+
+        assert_eq!(escapes(&|project| { project.behavior_file(absolute.clone(), sjson! {}); }), absolute);
+        assert_eq!(escapes(&|project| { project.resource_file("../../escaped.json", sjson! {}); }), PathBuf::from("eo_test_RP/../../escaped.json"));
+        assert_eq!(escapes(&|project| { project.behavior_file("", sjson! {}); }), PathBuf::from("eo_test_BP/"));
+        assert_eq!(escapes(&|project| { project.item(&Item::new("eo:../../../escaped")); }), PathBuf::from("eo_test_BP/items/eo/../../../escaped.json"));
+        assert_eq!(escapes(&|project| { project.block(&Block::new("../..:lamp")); }), PathBuf::from("eo_test_BP/blocks/../../lamp.json"));
+        assert_eq!(escapes(&|project| { project.entity(&Entity::new("/tmp:golem")); }), PathBuf::from("/tmp/golem.json"));
+        assert!(matches!(
+            Project::new("eo_test", "").behavior_file("../escaped.json", sjson! {}).write(&directory),
+            Err(PackError::InvalidPath(_))
+        ));
+        assert!(!directory.exists());
+    }
+
+    #[test]
+    fn lang_files() {
+        use crate::bedrock::components::Damage;
+        use crate::bedrock::Item;
+        use crate::lang::{LangFile, Localization, Localized};
+        use crate::pack::Project;
+        use crate::sjson::SJsonValue;
+
+        let existing = LangFile::parse("\u{feff}## Translated by hand\n\nitem.eo:sword.name=Schwert\t## not \"Degen\"\nitem.eo:old.name=Alt=Old\r\n").unwrap();
+        assert_eq!(existing.to_string(), "item.eo:sword.name=Schwert\nitem.eo:old.name=Alt=Old\n");
+        assert_eq!(LangFile::parse("a=b\nno separator").unwrap_err().to_string(), "line 2: expected `key=text`");
+        assert_eq!(LangFile::parse("=text").unwrap_err().to_string(), "line 1: the key is empty");
+
+        let title = Localized::new("eo.title", "Weapons").with("de_DE", "Waffen");
+        let x = sjson! {
+            title = $title
+        };
+        assert_eq!(x.to_string_compact().unwrap(), r#"{"title":"eo.title"}"#);
+
+        let document: SJsonValue = sjson! {
+            format_version = "1.21.0",
+            minecraft:item {
+                description {
+                    identifier = "eo:sword",
+                    display_name { en_US = "Sword", de_DE = "Degen" }
+                },
+                components {}
+            }
+        }.into();
+        let mut collected = document.clone();
+        let mut localization = Localization::new();
+        localization.collect(&mut collected);
+        assert!(collected.get("minecraft:item.description.display_name").is_none());
+        assert_eq!(localization.file("en_US").unwrap().get("item.eo:sword.name"), Some("Sword"));
+        assert_eq!(localization.file("de_DE").unwrap().get("item.eo:sword.name"), Some("Degen"));
+
+        let mut project = Project::new("eo_lang", "");
+        project
+            .behavior_file("items/sword.json", document.clone())
+            .behavior_file("blocks/lamp.json", sjson! {
+                minecraft:block { description { identifier = "eo:lamp", display_name = "Lamp" } }
+            })
+            .item(&Item::new("eo:axe").component(Damage { value: 5 }))
+            .translation("item.eo:axe.name", "Axe")
+            .localize(&title)
+            .lang_file("de_DE", existing);
+
+        assert_eq!(project.localization().missing("de_DE"), ["tile.eo:lamp.name", "item.eo:axe.name"]);
+
+        let files = project.render().unwrap();
+        let contents = |path: &str| files.iter().find(|file| file.path.ends_with(path)).map(|file| file.contents.replace([' ', '\n'], ""));
+        assert_eq!(contents("texts/languages.json").as_deref(), Some(r#"["en_US","de_DE"]"#));
+        assert_eq!(
+            files.iter().find(|file| file.path.ends_with("texts/en_US.lang")).unwrap().contents,
+            "item.eo:sword.name=Sword\ntile.eo:lamp.name=Lamp\nitem.eo:axe.name=Axe\neo.title=Weapons\n"
+        );
+        assert_eq!(
+            files.iter().find(|file| file.path.ends_with("texts/de_DE.lang")).unwrap().contents,
+            "item.eo:sword.name=Schwert\neo.title=Waffen\nitem.eo:old.name=Alt=Old\n"
+        );
+        assert_eq!(contents("blocks/lamp.json").as_deref(), Some(r#"{"minecraft:block":{"description":{"identifier":"eo:lamp"}}}"#));
+
+        // Existing files win even when the generated text is added after them.
+        let mut merged_first = Project::new("eo_lang", "");
+        merged_first
+            .lang_file("de_DE", LangFile::parse("item.eo:sword.name=Schwert\n").unwrap())
+            .behavior_file("items/sword.json", document)
+            .localize(&Localized::new("eo.title", "Weapons").with("de_DE", "Waffen"));
+
+        let german = merged_first.localization().file("de_DE").unwrap();
+        assert_eq!(german.to_string(), "item.eo:sword.name=Schwert\neo.title=Waffen\n");
+        assert_eq!(merged_first.localization().file("en_US").unwrap().get("item.eo:sword.name"), Some("Sword"));
+    }
 }
\ No newline at end of file
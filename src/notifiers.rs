@@ -1,13 +1,15 @@
 use std::sync::{Arc, RwLock};
 
+type ListenerType<T> = Arc<dyn Fn(&T)>;
+
 /// Notifiers are a tool to listen to variable updates.
 pub struct Notifier<T>
 where
     T: PartialEq,
     T: Clone {
     inner_value: Arc<RwLock<T>>,
-    listeners: RwLock<Vec<Arc<dyn Fn(&T)>>>,
-    explicit_listeners: RwLock<Vec<Arc<dyn Fn(&T)>>>,
+    listeners: RwLock<Vec<ListenerType<T>>>,
+    explicit_listeners: RwLock<Vec<ListenerType<T>>>,
 }
 
 impl<T> Notifier<T>
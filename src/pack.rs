@@ -0,0 +1,305 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use indexmap::IndexMap;
+use uuid::Uuid;
+use crate::bedrock::{Block, Entity, Item};
+use crate::lang::{LangFile, Localization, Localized, DEFAULT_LOCALE};
+use crate::sjson::{SJsonObject, SJsonValue, SerializeOptions, ToSJson};
+
+/// A pack or engine version, written as `[major, minor, patch]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl ToSJson for Version {
+    fn sjson(&self) -> SJsonValue {
+        [self.0, self.1, self.2].sjson()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackKind {
+    Behavior,
+    Resource,
+}
+
+impl PackKind {
+    /// The suffix of the pack directory, `BP` or `RP`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            PackKind::Behavior => "BP",
+            PackKind::Resource => "RP",
+        }
+    }
+
+    /// The manifest module type.
+    fn module_type(&self) -> &'static str {
+        match self {
+            PackKind::Behavior => "data",
+            PackKind::Resource => "resources",
+        }
+    }
+}
+
+/// A file of a generated pack, with the path relative to the output directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Why a [Project] couldn't be rendered or written.
+#[derive(Debug)]
+pub enum PackError {
+    /// A file would end up outside its pack, because its path, identifier, locale or the project name is absolute
+    /// or contains `..`.
+    InvalidPath(PathBuf),
+    Serialize(serde_json::Error),
+    Io(io::Error),
+}
+
+impl Display for PackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackError::InvalidPath(path) => write!(f, "`{}` is not a relative path inside the pack", path.display()),
+            PackError::Serialize(e) => write!(f, "couldn't serialize a pack file: {e}"),
+            PackError::Io(e) => write!(f, "couldn't write a pack file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PackError::InvalidPath(_) => None,
+            PackError::Serialize(e) => Some(e),
+            PackError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for PackError {
+    fn from(e: serde_json::Error) -> Self {
+        PackError::Serialize(e)
+    }
+}
+
+impl From<io::Error> for PackError {
+    fn from(e: io::Error) -> Self {
+        PackError::Io(e)
+    }
+}
+
+/// The UUIDs identifying a pack in its manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PackUuids {
+    pub header: Uuid,
+    pub module: Uuid,
+}
+
+impl PackUuids {
+    /// UUIDs derived from the project name, so generating the same project again produces the same manifest.
+    fn derived(project: &str, kind: PackKind) -> Self {
+        let name = |part: &str| format!("eo.pack.{project}.{}.{part}", kind.suffix());
+
+        Self {
+            header: Uuid::new_v5(&Uuid::NAMESPACE_OID, name("header").as_bytes()),
+            module: Uuid::new_v5(&Uuid::NAMESPACE_OID, name("module").as_bytes()),
+        }
+    }
+}
+
+/// A Bedrock add-on made of a behavior pack and a resource pack which depend on each other. Documents are collected in
+/// memory and written to `<name>_BP` and `<name>_RP` directories by [write](Project::write).
+/// ```rust
+/// use eo::bedrock::Item;
+/// use eo::pack::Project;
+///
+/// let mut project = Project::new("eo_weapons", "Swords and more");
+/// project.item(&Item::new("eo:sword"));
+/// project.translation("item.eo:sword.name", "Sword");
+///
+/// let files = project.dry_run().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Project {
+    pub name: String,
+    pub description: String,
+    pub version: Version,
+    pub min_engine_version: Version,
+    pub behavior_uuids: PackUuids,
+    pub resource_uuids: PackUuids,
+    pub options: SerializeOptions,
+    behavior_files: IndexMap<PathBuf, SJsonValue>,
+    resource_files: IndexMap<PathBuf, SJsonValue>,
+    localization: Localization,
+}
+
+impl Project {
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        let name = name.into();
+
+        Self {
+            behavior_uuids: PackUuids::derived(&name, PackKind::Behavior),
+            resource_uuids: PackUuids::derived(&name, PackKind::Resource),
+            name,
+            description: description.into(),
+            version: Version(1, 0, 0),
+            min_engine_version: Version(1, 21, 0),
+            options: SerializeOptions::default(),
+            behavior_files: IndexMap::new(),
+            resource_files: IndexMap::new(),
+            localization: Localization::new(),
+        }
+    }
+
+    /// Add a document to the behavior pack at `path`, e.g. `loot_tables/sword.json`. A document at the same path is replaced.
+    /// Display names are moved into the `.lang` files, see [Localization::collect].
+    pub fn behavior_file(&mut self, path: impl Into<PathBuf>, document: impl Into<SJsonValue>) -> &mut Self {
+        let mut document = document.into();
+        self.localization.collect(&mut document);
+
+        self.behavior_files.insert(path.into(), document);
+        self
+    }
+
+    /// Add a document to the resource pack at `path`, e.g. `textures/item_texture.json`. A document at the same path is replaced.
+    pub fn resource_file(&mut self, path: impl Into<PathBuf>, document: impl Into<SJsonValue>) -> &mut Self {
+        self.resource_files.insert(path.into(), document.into());
+        self
+    }
+
+    /// Add the document of an item at `items/<namespace>/<name>.json`. Blocks and entities are placed the same way.
+    pub fn item(&mut self, item: &Item) -> &mut Self {
+        self.behavior_file(Path::new("items").join(file_path(&item.identifier)), item.document())
+    }
+
+    pub fn block(&mut self, block: &Block) -> &mut Self {
+        self.behavior_file(Path::new("blocks").join(file_path(&block.identifier)), block.document())
+    }
+
+    pub fn entity(&mut self, entity: &Entity) -> &mut Self {
+        self.behavior_file(Path::new("entities").join(file_path(&entity.identifier)), entity.document())
+    }
+
+    /// Add an `en_US` translation to the resource pack, like `item.eo:sword.name=Sword`.
+    pub fn translation(&mut self, key: impl Into<String>, text: impl Into<String>) -> &mut Self {
+        self.localization.translate(DEFAULT_LOCALE, key, text);
+        self
+    }
+
+    /// Add the translations of a [Localized] text in every locale it has.
+    pub fn localize(&mut self, localized: &Localized) -> &mut Self {
+        self.localization.insert(localized);
+        self
+    }
+
+    /// Merge an existing `.lang` file for `locale`, whose texts win over generated ones added before or after it.
+    pub fn lang_file(&mut self, locale: impl Into<String>, existing: LangFile) -> &mut Self {
+        self.localization.merge_existing(locale, existing);
+        self
+    }
+
+    pub fn localization(&self) -> &Localization {
+        &self.localization
+    }
+
+    fn manifest(&self, kind: PackKind) -> SJsonValue {
+        let (uuids, dependency) = match kind {
+            PackKind::Behavior => (self.behavior_uuids, self.resource_uuids),
+            PackKind::Resource => (self.resource_uuids, self.behavior_uuids),
+        };
+
+        let object = |entries: Vec<(&str, SJsonValue)>| SJsonValue::Object(entries.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<SJsonObject>());
+
+        object(vec![
+            ("format_version", 2.sjson()),
+            ("header", object(vec![
+                ("name", format!("{} {}", self.name, kind.suffix()).sjson()),
+                ("description", self.description.sjson()),
+                ("uuid", uuids.header.to_string().sjson()),
+                ("version", self.version.sjson()),
+                ("min_engine_version", self.min_engine_version.sjson()),
+            ])),
+            ("modules", SJsonValue::Array(vec![object(vec![
+                ("type", kind.module_type().sjson()),
+                ("uuid", uuids.module.to_string().sjson()),
+                ("version", self.version.sjson()),
+            ])])),
+            ("dependencies", SJsonValue::Array(vec![object(vec![
+                ("uuid", dependency.header.to_string().sjson()),
+                ("version", self.version.sjson()),
+            ])])),
+        ])
+    }
+
+    /// Every file of both packs with its contents, without touching the disk.
+    /// Fails with [PackError::InvalidPath] if a file would end up outside its pack directory.
+    pub fn render(&self) -> Result<Vec<PackFile>, PackError> {
+        let mut files = vec![];
+
+        for (kind, documents) in [(PackKind::Behavior, &self.behavior_files), (PackKind::Resource, &self.resource_files)] {
+            let root = PathBuf::from(format!("{}_{}", self.name, kind.suffix()));
+            let mut file = |path: &Path, contents: String| files.push(PackFile { path: root.join(path), contents });
+
+            file(Path::new("manifest.json"), self.manifest(kind).to_string_with(self.options)?);
+            for (path, document) in documents {
+                file(path, document.to_string_with(self.options)?);
+            }
+
+            if kind == PackKind::Resource && !self.localization.is_empty() {
+                let locales: Vec<&str> = self.localization.files().map(|(locale, _)| locale).collect();
+                file(Path::new("texts/languages.json"), locales.sjson().to_string_with(self.options)?);
+
+                for (locale, lang) in self.localization.files() {
+                    file(&Path::new("texts").join(format!("{locale}.lang")), lang.to_string());
+                }
+            }
+        }
+
+        if let Some(file) = files.iter().find(|file| !is_inside_pack(&file.path)) {
+            return Err(PackError::InvalidPath(file.path.clone()));
+        }
+
+        Ok(files)
+    }
+
+    /// The paths [write](Project::write) would create, relative to the output directory.
+    pub fn dry_run(&self) -> Result<Vec<PathBuf>, PackError> {
+        Ok(self.render()?.into_iter().map(|file| file.path).collect())
+    }
+
+    /// Write both packs into `directory`, creating directories as needed. Returns the paths of the written files.
+    /// Nothing is written if any path is invalid.
+    pub fn write(&self, directory: impl AsRef<Path>) -> Result<Vec<PathBuf>, PackError> {
+        let mut written = vec![];
+
+        for file in self.render()? {
+            let path = directory.as_ref().join(&file.path);
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, file.contents)?;
+
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+}
+
+/// `eo:sword` becomes `eo/sword.json`, so identifiers which only differ in their namespace don't share a file.
+fn file_path(identifier: &str) -> PathBuf {
+    match identifier.split_once(':') {
+        Some((namespace, name)) => Path::new(namespace).join(format!("{name}.json")),
+        None => PathBuf::from(format!("{identifier}.json")),
+    }
+}
+
+/// Whether `path` is relative and only goes down, starting at a pack directory and ending at a file in it.
+fn is_inside_pack(path: &Path) -> bool {
+    let mut components = path.components().filter(|component| *component != Component::CurDir);
+
+    components.clone().count() >= 2 && components.all(|component| matches!(component, Component::Normal(_)))
+}
@@ -0,0 +1,124 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use serde::de::{DeserializeOwned, Error};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::events::Event;
+use crate::notifiers::Notifier;
+
+/// A single captured payload, with the time it was captured at relative to the start of the recording.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedPayload<T> {
+    pub offset: Duration,
+    pub value: T,
+}
+
+/// A log of captured payloads, which can be stored as JSON and replayed later.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording<T> {
+    pub entries: Vec<RecordedPayload<T>>,
+}
+
+impl<T> Recording<T> {
+    /// The recorded values, without their timestamps.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|entry| &entry.value)
+    }
+
+    /// Notify `event` with every recorded value, in the order they were recorded.
+    pub fn replay(&self, event: &Event<T>)
+    where
+        T: Clone {
+        self.values().for_each(|value| event.notify(value.clone()));
+    }
+
+    /// Set every recorded value on `notifier`, in the order they were recorded.
+    pub fn replay_notifier(&self, notifier: &Notifier<T>)
+    where
+        T: PartialEq,
+        T: Clone {
+        self.values().for_each(|value| notifier.set(value.clone()));
+    }
+
+    /// Serialize the recording as a JSON array of `{ "offset_nanos": .., "value": .. }` objects.
+    pub fn to_json(&self) -> serde_json::Result<String>
+    where
+        T: Serialize {
+        let entries = self.entries.iter().map(|entry| {
+            Ok(json!({
+                "offset_nanos": entry.offset.as_nanos() as u64,
+                "value": serde_json::to_value(&entry.value)?
+            }))
+        }).collect::<serde_json::Result<Vec<Value>>>()?;
+
+        serde_json::to_string_pretty(&entries)
+    }
+
+    /// Read a recording written by [to_json](Recording::to_json).
+    pub fn from_json(json: &str) -> serde_json::Result<Self>
+    where
+        T: DeserializeOwned {
+        let entries: Vec<Value> = serde_json::from_str(json)?;
+
+        let entries = entries.into_iter().map(|mut entry| {
+            let offset = entry.get("offset_nanos").and_then(Value::as_u64)
+                .ok_or_else(|| serde_json::Error::custom("every recorded payload needs an `offset_nanos` integer"))?;
+            let value = entry.get_mut("value").map(Value::take)
+                .ok_or_else(|| serde_json::Error::custom("every recorded payload needs a `value`"))?;
+
+            Ok(RecordedPayload { offset: Duration::from_nanos(offset), value: serde_json::from_value(value)? })
+        }).collect::<serde_json::Result<Vec<RecordedPayload<T>>>>()?;
+
+        Ok(Self { entries })
+    }
+}
+
+/// Captures the payloads of any number of [Event]s and [Notifier]s into a single timestamped [Recording].
+pub struct EventRecorder<T> {
+    start: Instant,
+    log: Arc<RwLock<Vec<RecordedPayload<T>>>>,
+}
+
+impl<T: Clone + 'static> EventRecorder<T> {
+    /// Create a new EventRecorder. Offsets are measured from this moment.
+    pub fn new() -> Self {
+        Self { start: Instant::now(), log: Arc::new(RwLock::new(Vec::new())) }
+    }
+
+    fn capture(&self) -> impl Fn(&T) + 'static {
+        let start = self.start;
+        let log = self.log.clone();
+
+        move |value| {
+            log.write().unwrap().push(RecordedPayload { offset: start.elapsed(), value: value.clone() });
+        }
+    }
+
+    /// Record every payload `event` is notified with from now on.
+    pub fn attach(&self, event: &Event<T>) {
+        event.subscribe(self.capture());
+    }
+
+    /// Record every value set on `notifier` from now on, including sets which don't change the value.
+    pub fn attach_notifier(&self, notifier: &Notifier<T>)
+    where
+        T: PartialEq {
+        notifier.listen_explicit(self.capture());
+    }
+
+    /// A snapshot of everything recorded so far.
+    pub fn recording(&self) -> Recording<T> {
+        Recording { entries: self.log.read().unwrap().clone() }
+    }
+
+    /// Forget everything recorded so far.
+    pub fn clear(&self) {
+        self.log.write().unwrap().clear();
+    }
+}
+
+impl<T: Clone + 'static> Default for EventRecorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
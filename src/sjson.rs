@@ -1,14 +1,45 @@
+mod diff;
+mod from;
+mod number;
+mod parser;
+mod patch;
+mod query;
+mod schema;
+mod suggest;
+mod writer;
+
 use std::collections::HashMap;
-use std::hash::Hash;
-use serde::{Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+use indexmap::IndexMap;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::sjson::parser::Parser;
+use crate::sjson::writer::{value_to_string, write_value};
 
-#[derive(Clone, Debug)]
+pub use crate::sjson::diff::{diff, render_diff, Change, ChangeKind};
+pub use crate::sjson::from::{from_sjson_field, from_sjson_index, FromSJson, FromSJsonError, PathSegment};
+pub use crate::sjson::number::SJsonNumber;
+pub use macros::{FromSJson, HasSJsonIdent, ToSJson};
+pub use crate::sjson::parser::ParseError;
+pub use crate::sjson::patch::{MergeStrategy, PatchError, PatchOperation};
+pub use crate::sjson::schema::{Schema, SchemaType, ValidationError};
+pub use crate::sjson::query::{Matches, Query, QueryError, QuerySegment};
+pub use crate::sjson::writer::{Indent, SerializeOptions};
+
+/// An sJSON object. Keeps its keys in insertion order, so serialized output is the same on every run.
+pub type SJsonObject = IndexMap<String, SJsonValue>;
+
+/// Objects compare equal regardless of key order, and numbers compare as described on [SJsonNumber].
+#[derive(Clone, Debug, PartialEq)]
 pub enum SJsonValue {
     String(String),
-    Number(f64),
+    Number(SJsonNumber),
     Boolean(bool),
-    Object(HashMap<String, SJsonValue>),
+    Object(SJsonObject),
     Array(Vec<SJsonValue>),
+    Null,
 }
 
 pub trait HasSJsonIdent {
@@ -24,7 +55,6 @@ pub struct SJsonMacro {
 pub struct SJsonElement {
     pub id: String,
     pub params: SJsonValue,
-    pub direct: Option<(String, String)>,
 }
 
 // impl Serialize for SJsonElement {
@@ -46,7 +76,7 @@ impl Serialize for SJsonValue {
                 s.serialize_str(v)
             }
             SJsonValue::Number(v) => {
-                s.serialize_f64(*v)
+                v.serialize(s)
             }
             SJsonValue::Boolean(v) => {
                 s.serialize_bool(*v)
@@ -57,28 +87,224 @@ impl Serialize for SJsonValue {
             SJsonValue::Array(v) => {
                 v.serialize(s)
             }
+            SJsonValue::Null => {
+                s.serialize_unit()
+            }
         }
     }
 }
 
-impl SJsonMacro {
-    pub fn serialize(&self) -> String {
-        let filter: Vec<SJsonElement> = self.vec.iter().filter(|v| v.direct.is_none()).cloned().collect();
-        let filter_dir: Vec<(String, String)> = self.vec.iter().cloned().filter_map(|v| v.direct ).collect();
-        let mapped = filter.transform_hashmap();
-        let mut ser = serde_json::to_string_pretty(&mapped).unwrap();
-        ser = ser.strip_prefix('{').unwrap().strip_suffix('}').unwrap().to_string();
-        for dir in filter_dir {
-            if (!ser.is_empty()) {
-                ser.push(',');
+impl SJsonValue {
+    /// Read JSON text into an SJsonValue.
+    /// ```rust
+    /// use eo::sjson::SJsonValue;
+    ///
+    /// let value = SJsonValue::parse(r#"{ "minecraft:icon": "icon_id" }"#).unwrap();
+    /// ```
+    pub fn parse(source: &str) -> Result<SJsonValue, ParseError> {
+        Parser::new(source).parse_document()
+    }
+}
+
+impl FromStr for SJsonValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SJsonValue::parse(s)
+    }
+}
+
+/// Writes compact sJSON, or pretty-printed sJSON with `{:#}`.
+impl Display for SJsonValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let options = if f.alternate() { SerializeOptions::default() } else { SerializeOptions::compact() };
+
+        f.write_str(&self.to_string_with(options).map_err(|_| std::fmt::Error)?)
+    }
+}
+
+struct SJsonValueVisitor;
+
+impl<'de> Visitor<'de> for SJsonValueVisitor {
+    type Value = SJsonValue;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str("a string, number, boolean, object, array or null")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Null)
+    }
+
+    fn visit_some<D>(self, d: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        SJsonValue::deserialize(d)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Boolean(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Number(v.into()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(SJsonValue::Number(v.into()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(SJsonValue::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(SJsonValue::String(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>
+    {
+        let mut array = Vec::new();
+
+        while let Some(value) = seq.next_element()? {
+            array.push(value);
+        }
+
+        Ok(SJsonValue::Array(array))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>
+    {
+        let mut object = SJsonObject::new();
+
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+
+        Ok(SJsonValue::Object(object))
+    }
+}
+
+impl<'de> Deserialize<'de> for SJsonValue {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        d.deserialize_any(SJsonValueVisitor)
+    }
+}
+
+impl SJsonValue {
+    /// The name of the JSON type of this value, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SJsonValue::String(_) => "string",
+            SJsonValue::Number(_) => "number",
+            SJsonValue::Boolean(_) => "boolean",
+            SJsonValue::Object(_) => "object",
+            SJsonValue::Array(_) => "array",
+            SJsonValue::Null => "null",
+        }
+    }
+
+    /// Whether both values are equal as JSON values: unlike `==`, numbers compare by value, so `1` equals `1.0`.
+    pub fn value_eq(&self, other: &SJsonValue) -> bool {
+        match (self, other) {
+            (SJsonValue::Number(a), SJsonValue::Number(b)) => a.value_eq(b),
+            (SJsonValue::Array(a), SJsonValue::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.value_eq(b)),
+            (SJsonValue::Object(a), SJsonValue::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| a.value_eq(b)))
             }
-            ser.push_str(&format!(
-                "\"{}\": {}", dir.0, dir.1
-            ));
+            (a, b) => a == b,
         }
+    }
 
-        format!("{{ {} }}", ser)
+    /// Write this value to `writer` as text.
+    pub fn to_writer<W: Write>(&self, writer: W, options: SerializeOptions) -> Result<(), serde_json::Error> {
+        write_value(writer, self, options)
     }
+
+    pub fn to_string_with(&self, options: SerializeOptions) -> Result<String, serde_json::Error> {
+        value_to_string(self, options)
+    }
+
+    pub fn to_string_compact(&self) -> Result<String, serde_json::Error> {
+        self.to_string_with(SerializeOptions::compact())
+    }
+
+    /// Sort the keys of this object and every nested object alphabetically.
+    pub fn sort_keys(&mut self) {
+        match self {
+            SJsonValue::Object(object) => {
+                object.sort_keys();
+                object.values_mut().for_each(SJsonValue::sort_keys);
+            }
+            SJsonValue::Array(array) => {
+                array.iter_mut().for_each(SJsonValue::sort_keys);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl SJsonMacro {
+    /// The elements of the macro as an object, in the order they were written.
+    /// A key written more than once keeps its first position and takes its last value.
+    pub fn to_object(&self) -> SJsonObject {
+        self.vec.iter().map(|v| (v.id.clone(), v.params.clone())).collect()
+    }
+
+    /// Write the elements to `writer` as text.
+    pub fn to_writer<W: Write>(&self, writer: W, options: SerializeOptions) -> Result<(), serde_json::Error> {
+        write_value(writer, &SJsonValue::Object(self.to_object()), options)
+    }
+
+    pub fn to_string_with(&self, options: SerializeOptions) -> Result<String, serde_json::Error> {
+        value_to_string(&SJsonValue::Object(self.to_object()), options)
+    }
+
+    pub fn to_string_compact(&self) -> Result<String, serde_json::Error> {
+        self.to_string_with(SerializeOptions::compact())
+    }
+
+    /// Serialize the elements pretty-printed, in the order they were written in the macro.
+    pub fn serialize(&self) -> Result<String, serde_json::Error> {
+        self.to_string_with(SerializeOptions::default())
+    }
+
+    /// Serialize the elements pretty-printed, with the keys of every object sorted alphabetically.
+    pub fn serialize_sorted(&self) -> Result<String, serde_json::Error> {
+        self.to_string_with(SerializeOptions::default().sort_keys())
+    }
+}
+
+/// Writes compact sJSON, or pretty-printed sJSON with `{:#}`.
+impl Display for SJsonMacro {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        SJsonValue::Object(self.to_object()).fmt(f)
+    }
+}
+
+/// The entry added by a `: $value` direct inclusion in the `sjson!` macro, keyed by the identifier of the value.
+/// Inclusions follow the same rule as every other key: if the identifier is already in the object,
+/// the value replaces the earlier one in its original position.
+pub fn direct_inclusion<T>(value: &T) -> (String, SJsonValue)
+where
+    T: HasSJsonIdent + ToSJson + ?Sized {
+    (value.sjson_ident(), value.sjson())
 }
 
 pub trait TransformHashMap {
@@ -99,6 +325,18 @@ impl From<SJsonMacro> for HashMap<String, SJsonValue> {
     }
 }
 
+impl From<SJsonMacro> for SJsonObject {
+    fn from(value: SJsonMacro) -> Self {
+        value.to_object()
+    }
+}
+
+impl From<SJsonMacro> for SJsonValue {
+    fn from(value: SJsonMacro) -> Self {
+        SJsonValue::Object(value.to_object())
+    }
+}
+
 pub trait ToSJson {
     fn sjson(&self) -> SJsonValue;
 }
@@ -121,29 +359,86 @@ impl ToSJson for bool {
     }
 }
 
-trait Number {}
+macro_rules! impl_to_sjson_number {
+    ($($t:ty),*) => {
+        $(
+            impl ToSJson for $t {
+                fn sjson(&self) -> SJsonValue {
+                    SJsonValue::Number(SJsonNumber::from(*self))
+                }
+            }
+        )*
+    };
+}
 
-impl Number for f64 {}
-impl Number for i32 {}
-impl Number for i64 {}
-impl Number for u32 {}
-impl Number for u64 {}
-impl Number for usize {}
-impl Number for isize {}
+impl_to_sjson_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
 
-impl<T> ToSJson for T
-where
-    T: Number + Into<f64> + Copy {
+impl ToSJson for SJsonNumber {
     fn sjson(&self) -> SJsonValue {
-        SJsonValue::Number((*self).into())
+        SJsonValue::Number(*self)
     }
 }
 
 impl<T> ToSJson for Vec<T>
 where
-    T: ToSJson + Copy {
+    T: ToSJson {
+    fn sjson(&self) -> SJsonValue {
+        self.as_slice().sjson()
+    }
+}
+
+impl<T> ToSJson for [T]
+where
+    T: ToSJson {
+    fn sjson(&self) -> SJsonValue {
+        SJsonValue::Array(self.iter().map(ToSJson::sjson).collect())
+    }
+}
+
+impl<T, const N: usize> ToSJson for [T; N]
+where
+    T: ToSJson {
+    fn sjson(&self) -> SJsonValue {
+        self.as_slice().sjson()
+    }
+}
+
+impl<T> ToSJson for Box<T>
+where
+    T: ToSJson + ?Sized {
+    fn sjson(&self) -> SJsonValue {
+        (**self).sjson()
+    }
+}
+
+/// HashMaps have no order, so their keys are sorted to keep the output deterministic.
+impl<T> ToSJson for HashMap<String, T>
+where
+    T: ToSJson {
+    fn sjson(&self) -> SJsonValue {
+        let mut object: SJsonObject = self.iter().map(|(k, v)| (k.clone(), v.sjson())).collect();
+        object.sort_keys();
+
+        SJsonValue::Object(object)
+    }
+}
+
+impl<T> ToSJson for IndexMap<String, T>
+where
+    T: ToSJson {
+    fn sjson(&self) -> SJsonValue {
+        SJsonValue::Object(self.iter().map(|(k, v)| (k.clone(), v.sjson())).collect())
+    }
+}
+
+impl<T> ToSJson for Option<T>
+where
+    T: ToSJson {
     fn sjson(&self) -> SJsonValue {
-        SJsonValue::Array(self.iter().map(|x| (*x).sjson()).collect())
+        match self {
+            Some(v) => v.sjson(),
+            None => SJsonValue::Null,
+        }
     }
 }
 
@@ -151,4 +446,130 @@ impl ToSJson for SJsonValue {
     fn sjson(&self) -> SJsonValue {
         self.clone()
     }
+}
+
+/// Values that can be spread into an object by `..$value` in the `sjson!` macro. Entries overwrite earlier keys, keeping their position.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be spread into an sJSON object",
+    label = "only maps, sJSON objects and `Option`s of them can be spread here"
+)]
+pub trait SpreadObject {
+    fn spread_object(&self) -> SJsonObject;
+}
+
+impl<T> SpreadObject for &T
+where
+    T: SpreadObject + ?Sized {
+    fn spread_object(&self) -> SJsonObject {
+        (**self).spread_object()
+    }
+}
+
+/// Keys are sorted, as in [ToSJson] for HashMaps.
+impl<T> SpreadObject for HashMap<String, T>
+where
+    T: ToSJson {
+    fn spread_object(&self) -> SJsonObject {
+        let mut object: SJsonObject = self.iter().map(|(k, v)| (k.clone(), v.sjson())).collect();
+        object.sort_keys();
+
+        object
+    }
+}
+
+impl<T> SpreadObject for IndexMap<String, T>
+where
+    T: ToSJson {
+    fn spread_object(&self) -> SJsonObject {
+        self.iter().map(|(k, v)| (k.clone(), v.sjson())).collect()
+    }
+}
+
+impl SpreadObject for SJsonMacro {
+    fn spread_object(&self) -> SJsonObject {
+        self.to_object()
+    }
+}
+
+/// `None` spreads nothing.
+impl<T> SpreadObject for Option<T>
+where
+    T: SpreadObject {
+    fn spread_object(&self) -> SJsonObject {
+        self.as_ref().map(SpreadObject::spread_object).unwrap_or_default()
+    }
+}
+
+/// The type of an [SJsonValue] is only known at runtime: an object spreads its entries and `null` spreads nothing.
+/// Panics for any other value, as its contents would otherwise be lost.
+impl SpreadObject for SJsonValue {
+    fn spread_object(&self) -> SJsonObject {
+        match self {
+            SJsonValue::Object(object) => object.clone(),
+            SJsonValue::Null => SJsonObject::new(),
+            other => panic!("Only objects can be spread into an sJSON object, found {}.", other.type_name()),
+        }
+    }
+}
+
+/// Values that can be spread into an array by `..$value` in the `sjson!` macro.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be spread into an sJSON array",
+    label = "only sequences, sJSON arrays and `Option`s of them can be spread here"
+)]
+pub trait SpreadArray {
+    fn spread_array(&self) -> Vec<SJsonValue>;
+}
+
+impl<T> SpreadArray for &T
+where
+    T: SpreadArray + ?Sized {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        (**self).spread_array()
+    }
+}
+
+impl<T> SpreadArray for Vec<T>
+where
+    T: ToSJson {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        self.as_slice().spread_array()
+    }
+}
+
+impl<T> SpreadArray for [T]
+where
+    T: ToSJson {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        self.iter().map(ToSJson::sjson).collect()
+    }
+}
+
+impl<T, const N: usize> SpreadArray for [T; N]
+where
+    T: ToSJson {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        self.as_slice().spread_array()
+    }
+}
+
+/// `None` spreads nothing.
+impl<T> SpreadArray for Option<T>
+where
+    T: SpreadArray {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        self.as_ref().map(SpreadArray::spread_array).unwrap_or_default()
+    }
+}
+
+/// The type of an [SJsonValue] is only known at runtime: an array spreads its values and `null` spreads nothing.
+/// Panics for any other value, as its contents would otherwise be lost.
+impl SpreadArray for SJsonValue {
+    fn spread_array(&self) -> Vec<SJsonValue> {
+        match self {
+            SJsonValue::Array(array) => array.clone(),
+            SJsonValue::Null => Vec::new(),
+            other => panic!("Only arrays can be spread into an sJSON array, found {}.", other.type_name()),
+        }
+    }
 }
\ No newline at end of file
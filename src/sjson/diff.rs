@@ -0,0 +1,119 @@
+use std::fmt::{Display, Formatter};
+use colored::Colorize;
+use crate::sjson::from::format_path;
+use crate::sjson::{PathSegment, SJsonValue};
+
+/// What happened to the value at the path of a [Change].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeKind {
+    Added(SJsonValue),
+    Removed(SJsonValue),
+    /// The value changed but kept its type.
+    Changed { from: SJsonValue, to: SJsonValue },
+    /// The value was replaced by one of another type, e.g. a number by a string.
+    TypeChanged { from: SJsonValue, to: SJsonValue },
+}
+
+/// A single difference found by [diff].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub path: Vec<PathSegment>,
+    pub kind: ChangeKind,
+}
+
+impl Change {
+    /// The path of the change, like `components.minecraft:damage.value`, or `(root)` for the whole document.
+    pub fn path_string(&self) -> String {
+        if self.path.is_empty() {
+            "(root)".to_string()
+        } else {
+            format_path(&self.path)
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = self.path_string();
+
+        match &self.kind {
+            ChangeKind::Added(value) => write!(f, "+ {path}: {value}"),
+            ChangeKind::Removed(value) => write!(f, "- {path}: {value}"),
+            ChangeKind::Changed { from, to } => write!(f, "~ {path}: {from} -> {to}"),
+            ChangeKind::TypeChanged { from, to } => write!(f, "! {path}: {from} ({}) -> {to} ({})", from.type_name(), to.type_name()),
+        }
+    }
+}
+
+/// Every difference between `a` and `b`, in document order. Objects are compared key by key, ignoring key order,
+/// and arrays element by element.
+/// ```rust
+/// use eo::sjson::{diff, SJsonValue};
+///
+/// let a = SJsonValue::parse(r#"{ "minecraft:damage": { "value": 1 } }"#).unwrap();
+/// let b = SJsonValue::parse(r#"{ "minecraft:damage": { "value": 7 } }"#).unwrap();
+///
+/// assert_eq!(diff(&a, &b)[0].to_string(), "~ minecraft:damage.value: 1 -> 7");
+/// ```
+pub fn diff(a: &SJsonValue, b: &SJsonValue) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_into(a, b, &mut vec![], &mut changes);
+
+    changes
+}
+
+fn diff_into(a: &SJsonValue, b: &SJsonValue, path: &mut Vec<PathSegment>, changes: &mut Vec<Change>) {
+    if a == b {
+        return;
+    }
+
+    match (a, b) {
+        (SJsonValue::Object(a), SJsonValue::Object(b)) => {
+            for (key, old) in a {
+                path.push(PathSegment::Key(key.clone()));
+                match b.get(key) {
+                    Some(new) => diff_into(old, new, path, changes),
+                    None => changes.push(Change { path: path.clone(), kind: ChangeKind::Removed(old.clone()) }),
+                }
+                path.pop();
+            }
+
+            for (key, new) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                path.push(PathSegment::Key(key.clone()));
+                changes.push(Change { path: path.clone(), kind: ChangeKind::Added(new.clone()) });
+                path.pop();
+            }
+        }
+        (SJsonValue::Array(a), SJsonValue::Array(b)) => {
+            for index in 0..a.len().max(b.len()) {
+                path.push(PathSegment::Index(index));
+                match (a.get(index), b.get(index)) {
+                    (Some(old), Some(new)) => diff_into(old, new, path, changes),
+                    (Some(old), None) => changes.push(Change { path: path.clone(), kind: ChangeKind::Removed(old.clone()) }),
+                    (None, Some(new)) => changes.push(Change { path: path.clone(), kind: ChangeKind::Added(new.clone()) }),
+                    (None, None) => unreachable!(),
+                }
+                path.pop();
+            }
+        }
+        (a, b) if a.type_name() == b.type_name() => {
+            changes.push(Change { path: path.clone(), kind: ChangeKind::Changed { from: a.clone(), to: b.clone() } });
+        }
+        (a, b) => changes.push(Change { path: path.clone(), kind: ChangeKind::TypeChanged { from: a.clone(), to: b.clone() } }),
+    }
+}
+
+/// Render changes one per line for a terminal: additions in green, removals in red, changes in yellow
+/// and type changes in magenta.
+pub fn render_diff(changes: &[Change]) -> String {
+    changes.iter().map(|change| {
+        let line = change.to_string();
+
+        match change.kind {
+            ChangeKind::Added(_) => line.green().to_string(),
+            ChangeKind::Removed(_) => line.red().to_string(),
+            ChangeKind::Changed { .. } => line.yellow().to_string(),
+            ChangeKind::TypeChanged { .. } => line.magenta().to_string(),
+        }
+    }).collect::<Vec<String>>().join("\n")
+}
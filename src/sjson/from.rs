@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use indexmap::IndexMap;
+use crate::sjson::{SJsonNumber, SJsonObject, SJsonValue};
+
+/// A step on the way from the root of a document to a nested value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Formats a path like `minecraft:damage.values[0]`.
+pub(crate) fn format_path(path: &[PathSegment]) -> String {
+    let mut formatted = String::new();
+
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !formatted.is_empty() {
+                    formatted.push('.');
+                }
+                formatted.push_str(key);
+            }
+            PathSegment::Index(index) => formatted.push_str(&format!("[{index}]")),
+        }
+    }
+
+    formatted
+}
+
+/// An error produced by [FromSJson], with the path of the value that failed to convert.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FromSJsonError {
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+impl FromSJsonError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { path: vec![], message: message.into() }
+    }
+
+    /// An error for a value of the wrong type, e.g. `expected number, found string`.
+    pub fn expected(expected: &str, found: &SJsonValue) -> Self {
+        Self::new(format!("expected {expected}, found {}", found.type_name()))
+    }
+
+    /// Prefix the path of the error with an object key.
+    pub fn at_key(mut self, key: impl Into<String>) -> Self {
+        self.path.insert(0, PathSegment::Key(key.into()));
+        self
+    }
+
+    /// Prefix the path of the error with an array index.
+    pub fn at_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+}
+
+impl Display for FromSJsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", format_path(&self.path), self.message)
+        }
+    }
+}
+
+impl std::error::Error for FromSJsonError {}
+
+/// Conversion from an [SJsonValue] back into a Rust type. The opposite of [ToSJson](crate::sjson::ToSJson).
+pub trait FromSJson: Sized {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError>;
+}
+
+/// Convert the value of `key` in `object`. A missing key is treated as `null`, so optional fields can be left out.
+pub fn from_sjson_field<T: FromSJson>(object: &SJsonObject, key: &str) -> Result<T, FromSJsonError> {
+    match object.get(key) {
+        Some(value) => T::from_sjson(value).map_err(|e| e.at_key(key)),
+        None => T::from_sjson(&SJsonValue::Null).map_err(|_| FromSJsonError::new(format!("missing field `{key}`"))),
+    }
+}
+
+/// Convert the element at `index` of `array`.
+pub fn from_sjson_index<T: FromSJson>(array: &[SJsonValue], index: usize) -> Result<T, FromSJsonError> {
+    match array.get(index) {
+        Some(value) => T::from_sjson(value).map_err(|e| e.at_index(index)),
+        None => Err(FromSJsonError::new(format!("missing array element {index}"))),
+    }
+}
+
+impl FromSJson for SJsonValue {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromSJson for bool {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Boolean(v) => Ok(*v),
+            other => Err(FromSJsonError::expected("boolean", other)),
+        }
+    }
+}
+
+impl FromSJson for String {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::String(v) => Ok(v.clone()),
+            other => Err(FromSJsonError::expected("string", other)),
+        }
+    }
+}
+
+impl FromSJson for SJsonNumber {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Number(v) => Ok(*v),
+            other => Err(FromSJsonError::expected("number", other)),
+        }
+    }
+}
+
+macro_rules! impl_from_sjson_integer {
+    ($($t:ty),*) => {
+        $(
+            impl FromSJson for $t {
+                fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+                    let number = SJsonNumber::from_sjson(value)?;
+
+                    let converted = match number {
+                        SJsonNumber::Int(v) => <$t>::try_from(v).ok(),
+                        SJsonNumber::UInt(v) => <$t>::try_from(v).ok(),
+                        SJsonNumber::Float(_) => return Err(FromSJsonError::new(format!("expected an integer, found {number}"))),
+                    };
+
+                    converted.ok_or_else(|| FromSJsonError::new(format!("{number} does not fit into {}", stringify!($t))))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_sjson_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl FromSJson for f64 {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        Ok(SJsonNumber::from_sjson(value)?.as_f64())
+    }
+}
+
+impl FromSJson for f32 {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        Ok(f64::from_sjson(value)? as f32)
+    }
+}
+
+impl<T: FromSJson> FromSJson for Option<T> {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Null => Ok(None),
+            other => T::from_sjson(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromSJson> FromSJson for Box<T> {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        T::from_sjson(value).map(Box::new)
+    }
+}
+
+impl<T: FromSJson> FromSJson for Vec<T> {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Array(array) => (0..array.len()).map(|i| from_sjson_index(array, i)).collect(),
+            other => Err(FromSJsonError::expected("array", other)),
+        }
+    }
+}
+
+impl<T: FromSJson> FromSJson for HashMap<String, T> {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Object(object) => object.keys().map(|key| Ok((key.clone(), from_sjson_field(object, key)?))).collect(),
+            other => Err(FromSJsonError::expected("object", other)),
+        }
+    }
+}
+
+impl<T: FromSJson> FromSJson for IndexMap<String, T> {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match value {
+            SJsonValue::Object(object) => object.keys().map(|key| Ok((key.clone(), from_sjson_field(object, key)?))).collect(),
+            other => Err(FromSJsonError::expected("object", other)),
+        }
+    }
+}
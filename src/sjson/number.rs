@@ -0,0 +1,147 @@
+use std::fmt::{Display, Formatter};
+use serde::{Serialize, Serializer};
+
+/// An sJSON number which remembers whether it was an integer, so `7` is written back as `7` and not `7.0`,
+/// and 64-bit integers keep their full precision.
+#[derive(Clone, Copy, Debug)]
+pub enum SJsonNumber {
+    /// A signed integer.
+    Int(i64),
+    /// An unsigned integer too large for [Int](SJsonNumber::Int).
+    UInt(u64),
+    Float(f64),
+}
+
+impl SJsonNumber {
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, SJsonNumber::Float(_))
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            SJsonNumber::Int(v) => v as f64,
+            SJsonNumber::UInt(v) => v as f64,
+            SJsonNumber::Float(v) => v,
+        }
+    }
+
+    /// The number as an `i64`, if it is an integer which fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            SJsonNumber::Int(v) => Some(v),
+            SJsonNumber::UInt(v) => i64::try_from(v).ok(),
+            SJsonNumber::Float(_) => None,
+        }
+    }
+
+    /// The number as a `u64`, if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            SJsonNumber::Int(v) => u64::try_from(v).ok(),
+            SJsonNumber::UInt(v) => Some(v),
+            SJsonNumber::Float(_) => None,
+        }
+    }
+
+    /// Whether both numbers have the same value regardless of representation, so unlike `==`, `1` equals `1.0`.
+    pub fn value_eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (SJsonNumber::Float(a), SJsonNumber::Float(b)) => a == b,
+            (SJsonNumber::Float(f), n) | (n, SJsonNumber::Float(f)) => f.fract() == 0.0 && n.as_i128() == Some(f as i128),
+            (a, b) => a == b,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match *self {
+            SJsonNumber::Int(v) => Some(v.into()),
+            SJsonNumber::UInt(v) => Some(v.into()),
+            SJsonNumber::Float(_) => None,
+        }
+    }
+}
+
+/// Integers compare by value regardless of representation. Integers and floats are never equal, as they serialize differently.
+impl PartialEq for SJsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SJsonNumber::Float(a), SJsonNumber::Float(b)) => a == b,
+            (SJsonNumber::Float(_), _) | (_, SJsonNumber::Float(_)) => false,
+            (a, b) => a.as_i64() == b.as_i64() && a.as_u64() == b.as_u64(),
+        }
+    }
+}
+
+impl Display for SJsonNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SJsonNumber::Int(v) => write!(f, "{v}"),
+            SJsonNumber::UInt(v) => write!(f, "{v}"),
+            SJsonNumber::Float(v) => write!(f, "{v:?}"),
+        }
+    }
+}
+
+impl Serialize for SJsonNumber {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        match *self {
+            SJsonNumber::Int(v) => s.serialize_i64(v),
+            SJsonNumber::UInt(v) => s.serialize_u64(v),
+            SJsonNumber::Float(v) => s.serialize_f64(v),
+        }
+    }
+}
+
+impl From<u64> for SJsonNumber {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(v) => SJsonNumber::Int(v),
+            Err(_) => SJsonNumber::UInt(value),
+        }
+    }
+}
+
+impl From<i64> for SJsonNumber {
+    fn from(value: i64) -> Self {
+        SJsonNumber::Int(value)
+    }
+}
+
+impl From<f64> for SJsonNumber {
+    fn from(value: f64) -> Self {
+        SJsonNumber::Float(value)
+    }
+}
+
+macro_rules! impl_from_number {
+    ($via:ty: $($t:ty),*) => {
+        $(
+            impl From<$t> for SJsonNumber {
+                fn from(value: $t) -> Self {
+                    SJsonNumber::from(value as $via)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i64: i8, i16, i32, isize);
+impl_from_number!(u64: u8, u16, u32, usize);
+impl_from_number!(f64: f32);
+
+impl From<i128> for SJsonNumber {
+    fn from(value: i128) -> Self {
+        i64::try_from(value).map(SJsonNumber::Int)
+            .or_else(|_| u64::try_from(value).map(SJsonNumber::UInt))
+            .unwrap_or(SJsonNumber::Float(value as f64))
+    }
+}
+
+impl From<u128> for SJsonNumber {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map(SJsonNumber::from).unwrap_or(SJsonNumber::Float(value as f64))
+    }
+}
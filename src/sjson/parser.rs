@@ -0,0 +1,310 @@
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+use crate::sjson::{SJsonNumber, SJsonObject, SJsonValue};
+
+/// An error produced while reading JSON text, with the 1-based position it occurred at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How deeply arrays and objects may be nested before parsing is aborted, so hostile input cannot overflow the stack.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+pub(crate) struct Parser<'s> {
+    chars: Peekable<Chars<'s>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'s> Parser<'s> {
+    pub(crate) fn new(source: &'s str) -> Self {
+        Self { chars: source.chars().peekable(), line: 1, column: 1 }
+    }
+
+    /// Parse a whole document: a single value, surrounded by nothing but whitespace.
+    pub(crate) fn parse_document(&mut self) -> Result<SJsonValue, ParseError> {
+        let value = self.parse_value(0)?;
+
+        self.skip_whitespace();
+
+        match self.chars.peek().copied() {
+            None => Ok(value),
+            Some(c) => Err(self.error(format!("unexpected `{c}` after the end of the document"))),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError { message: message.into(), line: self.line, column: self.column }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.chars.peek() {
+            Some(&c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(&c) => Err(self.error(format!("expected `{expected}`, found `{c}`"))),
+            None => Err(self.error(format!("expected `{expected}`, found the end of the document"))),
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<SJsonValue, ParseError> {
+        self.skip_whitespace();
+
+        if matches!(self.chars.peek(), Some('{' | '[')) && depth >= MAX_DEPTH {
+            return Err(self.error(format!("nesting exceeds the maximum depth of {MAX_DEPTH}")));
+        }
+
+        match self.chars.peek() {
+            Some('{') => self.parse_object(depth + 1),
+            Some('[') => self.parse_array(depth + 1),
+            Some('"') => Ok(SJsonValue::String(self.parse_string()?)),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some('t') => self.parse_keyword("true", SJsonValue::Boolean(true)),
+            Some('f') => self.parse_keyword("false", SJsonValue::Boolean(false)),
+            Some('n') => self.parse_keyword("null", SJsonValue::Null),
+            Some(&c) => Err(self.error(format!("expected a value, found `{c}`"))),
+            None => Err(self.error("expected a value, found the end of the document")),
+        }
+    }
+
+    fn parse_keyword(&mut self, keyword: &str, value: SJsonValue) -> Result<SJsonValue, ParseError> {
+        let error = self.error(format!("expected `{keyword}`"));
+
+        for expected in keyword.chars() {
+            if self.next() != Some(expected) {
+                return Err(error);
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<SJsonValue, ParseError> {
+        self.expect('{')?;
+
+        let mut object = SJsonObject::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(SJsonValue::Object(object));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+
+            let value = self.parse_value(depth)?;
+            object.insert(key, value);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(SJsonValue::Object(object)),
+                Some(c) => return Err(self.error(format!("expected `,` or `}}` in an object, found `{c}`"))),
+                None => return Err(self.error("unterminated object")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<SJsonValue, ParseError> {
+        self.expect('[')?;
+
+        let mut array = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(SJsonValue::Array(array));
+        }
+
+        loop {
+            array.push(self.parse_value(depth)?);
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(SJsonValue::Array(array)),
+                Some(c) => return Err(self.error(format!("expected `,` or `]` in an array, found `{c}`"))),
+                None => return Err(self.error("unterminated array")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        Some(c) => return Err(self.error(format!("invalid escape sequence `\\{c}`"))),
+                        None => return Err(self.error("unterminated string")),
+                    };
+
+                    string.push(escaped);
+                }
+                Some(c) if c < ' ' => return Err(self.error("control characters must be escaped inside strings")),
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let digit = self.next().and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("expected four hexadecimal digits in a `\\u` escape"))?;
+
+            code = code * 16 + digit;
+        }
+
+        Ok(code)
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.parse_hex4()?;
+
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("expected a low surrogate `\\u` escape after a high surrogate"));
+            }
+
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("invalid low surrogate in a `\\u` escape"));
+            }
+
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode code point in a `\\u` escape"))
+    }
+
+    fn take_digits(&mut self, into: &mut String) -> usize {
+        let mut count = 0;
+
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            into.push(c);
+            self.next();
+            count += 1;
+        }
+
+        count
+    }
+
+    fn parse_number(&mut self) -> Result<SJsonValue, ParseError> {
+        let error = self.error("invalid number");
+        let mut literal = String::new();
+        let mut integer = true;
+
+        if self.chars.peek() == Some(&'-') {
+            literal.push('-');
+            self.next();
+        }
+
+        match self.chars.peek() {
+            Some('0') => {
+                literal.push('0');
+                self.next();
+            }
+            Some('1'..='9') => {
+                self.take_digits(&mut literal);
+            }
+            _ => return Err(error),
+        }
+
+        if self.chars.peek() == Some(&'.') {
+            integer = false;
+            literal.push('.');
+            self.next();
+
+            if self.take_digits(&mut literal) == 0 {
+                return Err(self.error("expected digits after the decimal point"));
+            }
+        }
+
+        if matches!(self.chars.peek(), Some('e' | 'E')) {
+            integer = false;
+            literal.push('e');
+            self.next();
+
+            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                literal.push(sign);
+                self.next();
+            }
+
+            if self.take_digits(&mut literal) == 0 {
+                return Err(self.error("expected digits in the exponent"));
+            }
+        }
+
+        if integer {
+            if let Ok(v) = literal.parse::<i64>() {
+                return Ok(SJsonValue::Number(SJsonNumber::Int(v)));
+            }
+            if let Ok(v) = literal.parse::<u64>() {
+                return Ok(SJsonValue::Number(SJsonNumber::UInt(v)));
+            }
+        }
+
+        literal.parse::<f64>().map(|v| SJsonValue::Number(SJsonNumber::Float(v))).map_err(|_| error)
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::{Display, Formatter};
+use crate::sjson::query::{pointer_index, pointer_tokens};
+use crate::sjson::{from_sjson_field, FromSJson, FromSJsonError, SJsonObject, SJsonValue, ToSJson};
+
+/// How [SJsonValue::merge] combines two arrays. Objects are always merged key by key, and any other value is replaced.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum MergeStrategy {
+    /// The array from the other value replaces this one.
+    #[default]
+    Replace,
+    /// The elements of the other array are added after the elements of this one.
+    Append,
+    /// Objects whose value at the given key matches an element of this array are merged into that element,
+    /// everything else is appended. Useful for lists like `minecraft:events` entries keyed by `"name"`.
+    ByKey(String),
+}
+
+/// A single RFC 6902 JSON Patch operation. Paths are RFC 6901 JSON Pointers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOperation {
+    Add { path: String, value: SJsonValue },
+    Remove { path: String },
+    Replace { path: String, value: SJsonValue },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: SJsonValue },
+}
+
+/// A JSON Patch which couldn't be applied, with the index of the failing operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchError {
+    pub operation: usize,
+    pub message: String,
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation {}: {}", self.operation, self.message)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Escape an object key for use in a JSON Pointer.
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Split a pointer into the pointer of its parent and the last token. `None` for the root pointer.
+fn split_pointer(pointer: &str) -> Result<Option<(Vec<String>, String)>, String> {
+    let mut tokens = pointer_tokens(pointer).ok_or_else(|| format!("`{pointer}` is not a JSON Pointer"))?;
+
+    Ok(tokens.pop().map(|last| (tokens, last)))
+}
+
+fn resolve_mut<'a>(root: &'a mut SJsonValue, tokens: &[String], pointer: &str) -> Result<&'a mut SJsonValue, String> {
+    tokens.iter().try_fold(root, |value, token| match value {
+        SJsonValue::Object(object) => object.get_mut(token),
+        SJsonValue::Array(array) => array.get_mut(pointer_index(token)?),
+        _ => None,
+    }).ok_or_else(|| format!("`{pointer}` does not exist"))
+}
+
+fn add(root: &mut SJsonValue, pointer: &str, value: SJsonValue) -> Result<(), String> {
+    let Some((parent, last)) = split_pointer(pointer)? else {
+        *root = value;
+        return Ok(());
+    };
+
+    match resolve_mut(root, &parent, pointer)? {
+        SJsonValue::Object(object) => {
+            object.insert(last, value);
+        }
+        SJsonValue::Array(array) if last == "-" => array.push(value),
+        SJsonValue::Array(array) => match pointer_index(&last) {
+            Some(index) if index <= array.len() => array.insert(index, value),
+            _ => return Err(format!("`{pointer}` is not a valid position in the array")),
+        },
+        other => return Err(format!("can't add to {} at `{pointer}`", other.type_name())),
+    }
+
+    Ok(())
+}
+
+fn remove(root: &mut SJsonValue, pointer: &str) -> Result<SJsonValue, String> {
+    let Some((parent, last)) = split_pointer(pointer)? else {
+        return Err("the whole document can't be removed".to_string());
+    };
+
+    let removed = match resolve_mut(root, &parent, pointer)? {
+        SJsonValue::Object(object) => object.shift_remove(&last),
+        SJsonValue::Array(array) => pointer_index(&last).filter(|index| *index < array.len()).map(|index| array.remove(index)),
+        _ => None,
+    };
+
+    removed.ok_or_else(|| format!("`{pointer}` does not exist"))
+}
+
+fn apply_operation(root: &mut SJsonValue, operation: &PatchOperation) -> Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add(root, path, value.clone()),
+        PatchOperation::Remove { path } => remove(root, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            *root.pointer_mut(path).ok_or_else(|| format!("`{path}` does not exist"))? = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(format!("`{from}` can't be moved into one of its own children"));
+            }
+
+            let value = remove(root, from)?;
+            add(root, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = root.pointer(from).ok_or_else(|| format!("`{from}` does not exist"))?.clone();
+            add(root, path, value)
+        }
+        PatchOperation::Test { path, value } => match root.pointer(path) {
+            Some(found) if found.value_eq(value) => Ok(()),
+            Some(found) => Err(format!("test failed: `{path}` is {found}, expected {value}")),
+            None => Err(format!("test failed: `{path}` does not exist")),
+        },
+    }
+}
+
+fn diff_into(from: &SJsonValue, to: &SJsonValue, path: &str, operations: &mut Vec<PatchOperation>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (SJsonValue::Object(from), SJsonValue::Object(to)) => {
+            for key in from.keys().filter(|key| !to.contains_key(*key)) {
+                operations.push(PatchOperation::Remove { path: format!("{path}/{}", escape_token(key)) });
+            }
+
+            for (key, value) in to {
+                let child = format!("{path}/{}", escape_token(key));
+
+                match from.get(key) {
+                    Some(old) => diff_into(old, value, &child, operations),
+                    None => operations.push(PatchOperation::Add { path: child, value: value.clone() }),
+                }
+            }
+        }
+        (SJsonValue::Array(from), SJsonValue::Array(to)) => {
+            for (index, (old, new)) in from.iter().zip(to).enumerate() {
+                diff_into(old, new, &format!("{path}/{index}"), operations);
+            }
+
+            // Removing from the back keeps the earlier indices valid.
+            for index in (to.len()..from.len()).rev() {
+                operations.push(PatchOperation::Remove { path: format!("{path}/{index}") });
+            }
+
+            for (index, value) in to.iter().enumerate().skip(from.len()) {
+                operations.push(PatchOperation::Add { path: format!("{path}/{index}"), value: value.clone() });
+            }
+        }
+        _ => operations.push(PatchOperation::Replace { path: path.to_string(), value: to.clone() }),
+    }
+}
+
+impl SJsonValue {
+    /// Deep merge `other` into this value. Objects are merged key by key, keeping the position of existing keys,
+    /// arrays are combined according to `strategy`, and everything else is replaced by the value from `other`.
+    /// ```rust
+    /// use eo::sjson::{MergeStrategy, SJsonValue};
+    ///
+    /// let mut base = SJsonValue::parse(r#"{ "minecraft:damage": { "value": 1 }, "tags": ["a"] }"#).unwrap();
+    /// base.merge(SJsonValue::parse(r#"{ "minecraft:damage": { "value": 7 }, "tags": ["b"] }"#).unwrap(), &MergeStrategy::Append);
+    ///
+    /// assert_eq!(base.to_string(), r#"{"minecraft:damage":{"value":7},"tags":["a","b"]}"#);
+    /// ```
+    pub fn merge(&mut self, other: SJsonValue, strategy: &MergeStrategy) {
+        match (self, other) {
+            (SJsonValue::Object(object), SJsonValue::Object(other)) => {
+                for (key, value) in other {
+                    match object.get_mut(&key) {
+                        Some(existing) => existing.merge(value, strategy),
+                        None => {
+                            object.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (SJsonValue::Array(array), SJsonValue::Array(other)) => match strategy {
+                MergeStrategy::Replace => *array = other,
+                MergeStrategy::Append => array.extend(other),
+                MergeStrategy::ByKey(key) => {
+                    for value in other {
+                        let existing = value.get_key(key).and_then(|id| array.iter_mut().find(|element| element.get_key(key) == Some(id)));
+
+                        match existing {
+                            Some(existing) => existing.merge(value, strategy),
+                            None => array.push(value),
+                        }
+                    }
+                }
+            },
+            (this, other) => *this = other,
+        }
+    }
+
+    /// The value of `key` if this is an object.
+    fn get_key(&self, key: &str) -> Option<&SJsonValue> {
+        match self {
+            SJsonValue::Object(object) => object.get(key),
+            _ => None,
+        }
+    }
+
+    /// Apply an RFC 7386 JSON Merge Patch: objects are merged recursively, `null` removes a key and any other value replaces.
+    pub fn merge_patch(&mut self, patch: &SJsonValue) {
+        let SJsonValue::Object(patch) = patch else {
+            *self = patch.clone();
+            return;
+        };
+
+        if !matches!(self, SJsonValue::Object(_)) {
+            *self = SJsonValue::Object(SJsonObject::new());
+        }
+
+        let SJsonValue::Object(object) = self else { unreachable!() };
+
+        for (key, value) in patch {
+            if let SJsonValue::Null = value {
+                object.shift_remove(key);
+            } else {
+                object.entry(key.clone()).or_insert(SJsonValue::Null).merge_patch(value);
+            }
+        }
+    }
+
+    /// Apply an RFC 6902 JSON Patch. The patch is applied completely or not at all.
+    pub fn apply_patch(&mut self, patch: &[PatchOperation]) -> Result<(), PatchError> {
+        let mut patched = self.clone();
+
+        for (index, operation) in patch.iter().enumerate() {
+            apply_operation(&mut patched, operation).map_err(|message| PatchError { operation: index, message })?;
+        }
+
+        *self = patched;
+        Ok(())
+    }
+
+    /// The RFC 6902 JSON Patch which turns this value into `target`.
+    pub fn diff_patch(&self, target: &SJsonValue) -> Vec<PatchOperation> {
+        let mut operations = vec![];
+        diff_into(self, target, "", &mut operations);
+
+        operations
+    }
+}
+
+impl ToSJson for PatchOperation {
+    fn sjson(&self) -> SJsonValue {
+        let (op, from, path, value) = match self {
+            PatchOperation::Add { path, value } => ("add", None, path, Some(value)),
+            PatchOperation::Remove { path } => ("remove", None, path, None),
+            PatchOperation::Replace { path, value } => ("replace", None, path, Some(value)),
+            PatchOperation::Move { from, path } => ("move", Some(from), path, None),
+            PatchOperation::Copy { from, path } => ("copy", Some(from), path, None),
+            PatchOperation::Test { path, value } => ("test", None, path, Some(value)),
+        };
+
+        let mut object = SJsonObject::new();
+        object.insert("op".to_string(), op.sjson());
+        if let Some(from) = from {
+            object.insert("from".to_string(), from.sjson());
+        }
+        object.insert("path".to_string(), path.sjson());
+        if let Some(value) = value {
+            object.insert("value".to_string(), value.clone());
+        }
+
+        SJsonValue::Object(object)
+    }
+}
+
+impl FromSJson for PatchOperation {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        let SJsonValue::Object(object) = value else {
+            return Err(FromSJsonError::expected("object", value));
+        };
+
+        let op: String = from_sjson_field(object, "op")?;
+        let path = from_sjson_field(object, "path")?;
+        // `value` may legitimately be `null`, so it has to be present rather than just non-null.
+        let value = || object.get("value").cloned().ok_or_else(|| FromSJsonError::new("missing field `value`"));
+
+        Ok(match op.as_str() {
+            "add" => PatchOperation::Add { path, value: value()? },
+            "remove" => PatchOperation::Remove { path },
+            "replace" => PatchOperation::Replace { path, value: value()? },
+            "move" => PatchOperation::Move { from: from_sjson_field(object, "from")?, path },
+            "copy" => PatchOperation::Copy { from: from_sjson_field(object, "from")?, path },
+            "test" => PatchOperation::Test { path, value: value()? },
+            other => return Err(FromSJsonError::new(format!("unknown operation `{other}`")).at_key("op")),
+        })
+    }
+}
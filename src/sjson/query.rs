@@ -0,0 +1,305 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use crate::sjson::{SJsonObject, SJsonValue};
+
+/// One step of a [Query].
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuerySegment {
+    Key(String),
+    Index(usize),
+    /// Every value of an object or every element of an array.
+    Wildcard,
+}
+
+/// A path into an [SJsonValue], like `components.minecraft:damage.value`, `eo[0].woah` or `components.*.value`.
+/// Keys are separated by `.`, array elements are written as `[index]`, keys containing `.` or `[` can be quoted
+/// as `["1.20.0"]`, and `*` or `[*]` matches every child.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub segments: Vec<QuerySegment>,
+}
+
+/// A malformed [Query], with the byte offset it was found at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Query, QueryError> {
+        let chars: Vec<(usize, char)> = source.char_indices().collect();
+        let error = |message: &str, index: usize| QueryError {
+            message: message.to_string(),
+            position: chars.get(index).map_or(source.len(), |(position, _)| *position),
+        };
+
+        let mut segments = vec![];
+        let mut i = 0;
+
+        while i < chars.len() {
+            if !segments.is_empty() || chars[i].1 == '[' {
+                match chars[i].1 {
+                    '.' if !segments.is_empty() => i += 1,
+                    '[' => {}
+                    _ => return Err(error("expected `.` or `[`", i)),
+                }
+            }
+
+            if chars.get(i).is_some_and(|(_, c)| *c == '[') {
+                i += 1;
+
+                let segment = if chars.get(i).is_some_and(|(_, c)| *c == '"') {
+                    let mut key = String::new();
+                    i += 1;
+
+                    loop {
+                        match chars.get(i) {
+                            Some((_, '"')) => break,
+                            Some((_, '\\')) if chars.get(i + 1).is_some() => {
+                                key.push(chars[i + 1].1);
+                                i += 2;
+                            }
+                            Some((_, c)) => {
+                                key.push(*c);
+                                i += 1;
+                            }
+                            None => return Err(error("unterminated quoted key", i)),
+                        }
+                    }
+
+                    i += 1;
+                    QuerySegment::Key(key)
+                } else if chars.get(i).is_some_and(|(_, c)| *c == '*') {
+                    i += 1;
+                    QuerySegment::Wildcard
+                } else {
+                    let start = i;
+                    while chars.get(i).is_some_and(|(_, c)| c.is_ascii_digit()) {
+                        i += 1;
+                    }
+
+                    let digits: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                    QuerySegment::Index(digits.parse().map_err(|_| error("expected an array index, `*` or a quoted key", start))?)
+                };
+
+                if chars.get(i).is_none_or(|(_, c)| *c != ']') {
+                    return Err(error("expected `]`", i));
+                }
+
+                i += 1;
+                segments.push(segment);
+            } else {
+                let start = i;
+                while chars.get(i).is_some_and(|(_, c)| !matches!(c, '.' | '[' | ']')) {
+                    i += 1;
+                }
+
+                let key: String = chars[start..i].iter().map(|(_, c)| c).collect();
+                segments.push(match key.as_str() {
+                    "" => return Err(error("expected a key", start)),
+                    "*" => QuerySegment::Wildcard,
+                    _ => QuerySegment::Key(key),
+                });
+            }
+        }
+
+        Ok(Query { segments })
+    }
+
+    /// Every value in `root` matched by this query, in document order.
+    pub fn matches<'a>(&self, root: &'a SJsonValue) -> Matches<'a> {
+        Matches { segments: self.segments.clone(), stack: vec![(root, 0)] }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Query::parse(s)
+    }
+}
+
+/// The values matched by a [Query]. Created by [SJsonValue::query] and [Query::matches].
+#[derive(Debug)]
+pub struct Matches<'a> {
+    segments: Vec<QuerySegment>,
+    stack: Vec<(&'a SJsonValue, usize)>,
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = &'a SJsonValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((value, depth)) = self.stack.pop() {
+            let Some(segment) = self.segments.get(depth) else {
+                return Some(value);
+            };
+
+            match (segment, value) {
+                (QuerySegment::Key(key), SJsonValue::Object(object)) => {
+                    if let Some(child) = object.get(key) {
+                        self.stack.push((child, depth + 1));
+                    }
+                }
+                (QuerySegment::Index(index), SJsonValue::Array(array)) => {
+                    if let Some(child) = array.get(*index) {
+                        self.stack.push((child, depth + 1));
+                    }
+                }
+                (QuerySegment::Wildcard, SJsonValue::Object(object)) => {
+                    self.stack.extend(object.values().rev().map(|child| (child, depth + 1)));
+                }
+                (QuerySegment::Wildcard, SJsonValue::Array(array)) => {
+                    self.stack.extend(array.iter().rev().map(|child| (child, depth + 1)));
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+/// Decode the reference tokens of an RFC 6901 JSON Pointer. `None` if it doesn't start with `/`.
+pub(crate) fn pointer_tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(vec![]);
+    }
+
+    let tokens = pointer.strip_prefix('/')?;
+
+    Some(tokens.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// An array index token as defined by RFC 6901: digits without leading zeros.
+pub(crate) fn pointer_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    token.parse().ok()
+}
+
+impl SJsonValue {
+    /// The value at `path`, written as a [Query] without wildcards. A malformed path or one containing wildcards matches nothing.
+    /// ```rust
+    /// use eo::sjson::SJsonValue;
+    ///
+    /// let value = SJsonValue::parse(r#"{ "minecraft:damage": { "values": [1, 7] } }"#).unwrap();
+    /// assert!(value.get("minecraft:damage.values[1]").is_some());
+    /// ```
+    pub fn get(&self, path: &str) -> Option<&SJsonValue> {
+        let query = Query::parse(path).ok()?;
+
+        query.segments.iter().try_fold(self, |value, segment| match (segment, value) {
+            (QuerySegment::Key(key), SJsonValue::Object(object)) => object.get(key),
+            (QuerySegment::Index(index), SJsonValue::Array(array)) => array.get(*index),
+            _ => None,
+        })
+    }
+
+    /// The value at `path`, mutably. See [get](SJsonValue::get).
+    pub fn get_mut(&mut self, path: &str) -> Option<&mut SJsonValue> {
+        let query = Query::parse(path).ok()?;
+
+        query.segments.iter().try_fold(self, |value, segment| match (segment, value) {
+            (QuerySegment::Key(key), SJsonValue::Object(object)) => object.get_mut(key),
+            (QuerySegment::Index(index), SJsonValue::Array(array)) => array.get_mut(*index),
+            _ => None,
+        })
+    }
+
+    /// The value at an RFC 6901 JSON Pointer, like `/minecraft:damage/values/1`. `""` is the whole value.
+    pub fn pointer(&self, pointer: &str) -> Option<&SJsonValue> {
+        pointer_tokens(pointer)?.iter().try_fold(self, |value, token| match value {
+            SJsonValue::Object(object) => object.get(token),
+            SJsonValue::Array(array) => array.get(pointer_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// The value at an RFC 6901 JSON Pointer, mutably.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut SJsonValue> {
+        pointer_tokens(pointer)?.iter().try_fold(self, |value, token| match value {
+            SJsonValue::Object(object) => object.get_mut(token),
+            SJsonValue::Array(array) => array.get_mut(pointer_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Every value matched by a [Query], which may contain wildcards.
+    /// ```rust
+    /// use eo::sjson::SJsonValue;
+    ///
+    /// let value = SJsonValue::parse(r#"{ "components": { "a": { "value": 1 }, "b": { "value": 2 } } }"#).unwrap();
+    /// assert_eq!(value.query("components.*.value").unwrap().count(), 2);
+    /// ```
+    pub fn query(&self, query: &str) -> Result<Matches<'_>, QueryError> {
+        Ok(Query::parse(query)?.matches(self))
+    }
+}
+
+static NULL: SJsonValue = SJsonValue::Null;
+
+/// Looks up a key of an object. Missing keys and non-objects give `null`, so lookups can be chained.
+impl Index<&str> for SJsonValue {
+    type Output = SJsonValue;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            SJsonValue::Object(object) => object.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+/// Looks up an element of an array. Out of bounds indices and non-arrays give `null`, so lookups can be chained.
+impl Index<usize> for SJsonValue {
+    type Output = SJsonValue;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            SJsonValue::Array(array) => array.get(index).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+/// Inserts `null` for a missing key, and turns `null` into an empty object first, so nested objects can be built with
+/// `value["a"]["b"] = ...`. Panics on any other type.
+impl IndexMut<&str> for SJsonValue {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        if let SJsonValue::Null = self {
+            *self = SJsonValue::Object(SJsonObject::new());
+        }
+
+        match self {
+            SJsonValue::Object(object) => object.entry(key.to_string()).or_insert(SJsonValue::Null),
+            other => panic!("Can't index into {} with key `{key}`.", other.type_name()),
+        }
+    }
+}
+
+/// Panics if the value isn't an array or the index is out of bounds.
+impl IndexMut<usize> for SJsonValue {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self {
+            SJsonValue::Array(array) => {
+                let len = array.len();
+                array.get_mut(index).unwrap_or_else(|| panic!("Index {index} is out of bounds for an array of length {len}."))
+            }
+            other => panic!("Can't index into {} with index {index}.", other.type_name()),
+        }
+    }
+}
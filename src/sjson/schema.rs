@@ -0,0 +1,309 @@
+use std::fmt::{Display, Formatter};
+use indexmap::IndexMap;
+use regex::Regex;
+use crate::sjson::from::format_path;
+use crate::sjson::suggest::closest_key;
+use crate::sjson::{from_sjson_field, FromSJson, FromSJsonError, PathSegment, SJsonValue};
+
+/// The JSON type a [Schema] accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaType {
+    String,
+    Number,
+    /// A number without a fractional part.
+    Integer,
+    Boolean,
+    Object,
+    Array,
+    Null,
+}
+
+impl SchemaType {
+    fn name(&self) -> &'static str {
+        match self {
+            SchemaType::String => "string",
+            SchemaType::Number => "number",
+            SchemaType::Integer => "integer",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Object => "object",
+            SchemaType::Array => "array",
+            SchemaType::Null => "null",
+        }
+    }
+
+    fn matches(&self, value: &SJsonValue) -> bool {
+        match (self, value) {
+            (SchemaType::Integer, SJsonValue::Number(number)) => number.is_integer() || number.as_f64().fract() == 0.0,
+            (SchemaType::Integer, _) => false,
+            (other, value) => other.name() == value.type_name(),
+        }
+    }
+}
+
+/// A description of valid sJSON documents. Build one in Rust, or load a JSON Schema subset
+/// (`type`, `required`, `properties`, `additionalProperties`, `enum`, `minimum`, `maximum`, `pattern` and `items`)
+/// with [FromSJson].
+/// ```rust
+/// use eo::sjson::{Schema, SJsonValue};
+///
+/// let schema = Schema::object()
+///     .property("minecraft:damage", Schema::integer().minimum(0.0))
+///     .required("minecraft:damage")
+///     .deny_unknown_properties();
+///
+/// let errors = schema.validate(&SJsonValue::parse(r#"{ "minecraft:damge": 7 }"#).unwrap()).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "missing required property `minecraft:damage`");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Schema {
+    /// Accepted types. Empty accepts any type.
+    pub types: Vec<SchemaType>,
+    pub required: Vec<String>,
+    pub properties: IndexMap<String, Schema>,
+    /// Whether keys not listed in [properties](Schema::properties) are allowed.
+    pub additional_properties: bool,
+    /// The only values allowed, if set. Numbers compare by value, so `1` matches `1.0`.
+    pub enumeration: Option<Vec<SJsonValue>>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    /// A regular expression strings have to match somewhere, as in JSON Schema.
+    pub pattern: Option<Regex>,
+    /// The schema every element of an array has to match.
+    pub items: Option<Box<Schema>>,
+}
+
+/// A value which doesn't match a [Schema], with the path to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", format_path(&self.path), self.message)
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Schema::any()
+    }
+}
+
+impl Schema {
+    /// A schema accepting any value.
+    pub fn any() -> Self {
+        Self {
+            types: vec![],
+            required: vec![],
+            properties: IndexMap::new(),
+            additional_properties: true,
+            enumeration: None,
+            minimum: None,
+            maximum: None,
+            pattern: None,
+            items: None,
+        }
+    }
+
+    pub fn of_type(schema_type: SchemaType) -> Self {
+        Self { types: vec![schema_type], ..Self::any() }
+    }
+
+    pub fn string() -> Self {
+        Self::of_type(SchemaType::String)
+    }
+
+    pub fn number() -> Self {
+        Self::of_type(SchemaType::Number)
+    }
+
+    pub fn integer() -> Self {
+        Self::of_type(SchemaType::Integer)
+    }
+
+    pub fn boolean() -> Self {
+        Self::of_type(SchemaType::Boolean)
+    }
+
+    pub fn object() -> Self {
+        Self::of_type(SchemaType::Object)
+    }
+
+    pub fn array(items: Schema) -> Self {
+        Self { items: Some(Box::new(items)), ..Self::of_type(SchemaType::Array) }
+    }
+
+    /// Also accept `schema_type`.
+    pub fn or_type(mut self, schema_type: SchemaType) -> Self {
+        self.types.push(schema_type);
+        self
+    }
+
+    pub fn property(mut self, key: impl Into<String>, schema: Schema) -> Self {
+        self.properties.insert(key.into(), schema);
+        self
+    }
+
+    pub fn required(mut self, key: impl Into<String>) -> Self {
+        self.required.push(key.into());
+        self
+    }
+
+    /// Reject keys not listed with [property](Schema::property), which catches misspelled component names.
+    pub fn deny_unknown_properties(mut self) -> Self {
+        self.additional_properties = false;
+        self
+    }
+
+    pub fn enumeration(mut self, values: impl IntoIterator<Item = SJsonValue>) -> Self {
+        self.enumeration = Some(values.into_iter().collect());
+        self
+    }
+
+    pub fn minimum(mut self, minimum: f64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    pub fn maximum(mut self, maximum: f64) -> Self {
+        self.maximum = Some(maximum);
+        self
+    }
+
+    /// Panics if `pattern` isn't a valid regular expression.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(Regex::new(pattern).unwrap_or_else(|e| panic!("Invalid schema pattern `{pattern}`: {e}")));
+        self
+    }
+
+    /// Check `value` against this schema, collecting every error instead of stopping at the first one.
+    pub fn validate(&self, value: &SJsonValue) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_into(value, &mut vec![], &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_into(&self, value: &SJsonValue, path: &mut Vec<PathSegment>, errors: &mut Vec<ValidationError>) {
+        let mut error = |message: String| errors.push(ValidationError { path: path.clone(), message });
+
+        if !self.types.is_empty() && !self.types.iter().any(|t| t.matches(value)) {
+            let expected = self.types.iter().map(SchemaType::name).collect::<Vec<&str>>().join(" or ");
+            error(format!("expected {expected}, found {}", value.type_name()));
+            return;
+        }
+
+        if let Some(values) = self.enumeration.as_ref().filter(|values| !values.iter().any(|allowed| allowed.value_eq(value))) {
+            let allowed = values.iter().map(ToString::to_string).collect::<Vec<String>>().join(", ");
+            error(format!("{value} is not one of {allowed}"));
+        }
+
+        match value {
+            SJsonValue::Number(number) => {
+                if let Some(minimum) = self.minimum.filter(|minimum| number.as_f64() < *minimum) {
+                    error(format!("{number} is less than the minimum of {minimum}"));
+                }
+                if let Some(maximum) = self.maximum.filter(|maximum| number.as_f64() > *maximum) {
+                    error(format!("{number} is greater than the maximum of {maximum}"));
+                }
+            }
+            SJsonValue::String(string) => {
+                if let Some(pattern) = self.pattern.as_ref().filter(|pattern| !pattern.is_match(string)) {
+                    error(format!("{value} does not match the pattern `{pattern}`"));
+                }
+            }
+            SJsonValue::Object(object) => {
+                for key in self.required.iter().filter(|key| !object.contains_key(*key)) {
+                    error(format!("missing required property `{key}`"));
+                }
+
+                for (key, child) in object {
+                    path.push(PathSegment::Key(key.clone()));
+
+                    match self.properties.get(key) {
+                        Some(schema) => schema.validate_into(child, path, errors),
+                        None if !self.additional_properties => {
+                            let message = match closest_key(key, self.properties.keys()) {
+                                Some(suggestion) => format!("unknown property, did you mean `{suggestion}`?"),
+                                None => "unknown property".to_string(),
+                            };
+                            errors.push(ValidationError { path: path.clone(), message });
+                        }
+                        None => {}
+                    }
+
+                    path.pop();
+                }
+            }
+            SJsonValue::Array(array) => {
+                if let Some(items) = &self.items {
+                    for (index, element) in array.iter().enumerate() {
+                        path.push(PathSegment::Index(index));
+                        items.validate_into(element, path, errors);
+                        path.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FromSJson for SchemaType {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        match String::from_sjson(value)?.as_str() {
+            "string" => Ok(SchemaType::String),
+            "number" => Ok(SchemaType::Number),
+            "integer" => Ok(SchemaType::Integer),
+            "boolean" => Ok(SchemaType::Boolean),
+            "object" => Ok(SchemaType::Object),
+            "array" => Ok(SchemaType::Array),
+            "null" => Ok(SchemaType::Null),
+            other => Err(FromSJsonError::new(format!("unknown type `{other}`"))),
+        }
+    }
+}
+
+/// Loads the supported subset of JSON Schema. Other keywords are ignored.
+impl FromSJson for Schema {
+    fn from_sjson(value: &SJsonValue) -> Result<Self, FromSJsonError> {
+        let object = match value {
+            SJsonValue::Boolean(true) => return Ok(Schema::any()),
+            SJsonValue::Object(object) => object,
+            other => return Err(FromSJsonError::expected("object", other)),
+        };
+
+        let types = match object.get("type") {
+            Some(SJsonValue::Array(_)) => from_sjson_field(object, "type")?,
+            Some(_) => vec![from_sjson_field(object, "type")?],
+            None => vec![],
+        };
+        let pattern = from_sjson_field::<Option<String>>(object, "pattern")?
+            .map(|pattern| Regex::new(&pattern).map_err(|e| FromSJsonError::new(e.to_string()).at_key("pattern")))
+            .transpose()?;
+
+        Ok(Schema {
+            types,
+            required: from_sjson_field::<Option<Vec<String>>>(object, "required")?.unwrap_or_default(),
+            properties: from_sjson_field::<Option<IndexMap<String, Schema>>>(object, "properties")?.unwrap_or_default(),
+            additional_properties: from_sjson_field::<Option<bool>>(object, "additionalProperties")?.unwrap_or(true),
+            enumeration: from_sjson_field(object, "enum")?,
+            minimum: from_sjson_field(object, "minimum")?,
+            maximum: from_sjson_field(object, "maximum")?,
+            pattern,
+            items: from_sjson_field(object, "items")?,
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::Write;
+use serde::Serialize;
+use serde_json::ser::{CompactFormatter, PrettyFormatter};
+use serde_json::Serializer;
+use crate::sjson::SJsonValue;
+
+/// How nested values are indented when serializing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    /// Everything on one line, without any whitespace.
+    Compact,
+    /// One tab per level.
+    Tabs,
+    /// The given number of spaces per level.
+    Spaces(usize),
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(2)
+    }
+}
+
+/// Options for writing sJSON as text. The default is pretty-printed with two spaces, keeping key order.
+/// ```rust
+/// use eo::sjson::{Indent, SerializeOptions};
+///
+/// let options = SerializeOptions::default().indent(Indent::Tabs).sort_keys();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    pub indent: Indent,
+    pub sort_keys: bool,
+}
+
+impl SerializeOptions {
+    pub fn compact() -> Self {
+        Self { indent: Indent::Compact, sort_keys: false }
+    }
+
+    pub fn indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sort the keys of every object alphabetically instead of keeping the order they were written in.
+    pub fn sort_keys(mut self) -> Self {
+        self.sort_keys = true;
+        self
+    }
+}
+
+pub(crate) fn write_value<W: Write>(writer: W, value: &SJsonValue, options: SerializeOptions) -> Result<(), serde_json::Error> {
+    if options.sort_keys {
+        let mut sorted = value.clone();
+        sorted.sort_keys();
+
+        return write_value(writer, &sorted, SerializeOptions { sort_keys: false, ..options });
+    }
+
+    match options.indent {
+        Indent::Compact => value.serialize(&mut Serializer::with_formatter(writer, CompactFormatter)),
+        Indent::Tabs => value.serialize(&mut Serializer::with_formatter(writer, PrettyFormatter::with_indent(b"\t"))),
+        Indent::Spaces(width) => {
+            let indent = " ".repeat(width);
+
+            value.serialize(&mut Serializer::with_formatter(writer, PrettyFormatter::with_indent(indent.as_bytes())))
+        }
+    }
+}
+
+pub(crate) fn value_to_string(value: &SJsonValue, options: SerializeOptions) -> Result<String, serde_json::Error> {
+    let mut buffer = Vec::new();
+    write_value(&mut buffer, value, options)?;
+
+    // serde_json only ever writes valid UTF-8.
+    Ok(String::from_utf8(buffer).expect("serde_json produced invalid UTF-8"))
+}
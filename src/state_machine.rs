@@ -0,0 +1,151 @@
+use std::sync::{Arc, RwLock};
+use crate::events::Event;
+use crate::notifiers::Notifier;
+
+type GuardType<'a, S, E> = Arc<dyn Fn(&S, &E) -> bool + 'a>;
+type HookType<'a, S, E> = Arc<dyn Fn(&S, &E) + 'a>;
+
+/// A single entry of the transition table.
+pub struct Transition<'a, S, E> {
+    pub from: S,
+    pub event: E,
+    pub to: S,
+    guard: Option<GuardType<'a, S, E>>,
+}
+
+fn allows<S, E>(guard: &Option<GuardType<'_, S, E>>, state: &S, event: &E) -> bool {
+    guard.as_ref().is_none_or(|guard| guard(state, event))
+}
+
+/// Why a transition was not performed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RejectionReason {
+    /// There is no transition declared for this state and event.
+    Undeclared,
+    /// Transitions were declared, but every guard refused it.
+    Guarded,
+}
+
+/// Published through [StateMachine::rejected] when [StateMachine::fire] does not change the state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rejection<S, E> {
+    pub state: S,
+    pub event: E,
+    pub reason: RejectionReason,
+}
+
+/// A state machine with a declared transition table, backed by a [Notifier] holding the current state.
+pub struct StateMachine<'a, S, E>
+where
+    S: PartialEq,
+    S: Clone {
+    state: Notifier<S>,
+    transitions: RwLock<Vec<Transition<'a, S, E>>>,
+    enter_hooks: RwLock<Vec<(S, HookType<'a, S, E>)>>,
+    exit_hooks: RwLock<Vec<(S, HookType<'a, S, E>)>>,
+    rejected: Event<'a, Rejection<S, E>>,
+}
+
+impl<'a, S, E> StateMachine<'a, S, E>
+where
+    S: PartialEq,
+    S: Clone,
+    E: PartialEq,
+    E: Clone {
+    /// Create a new StateMachine with an empty transition table.
+    pub fn new(initial_state: S) -> Self {
+        Self {
+            state: Notifier::new(initial_state),
+            transitions: RwLock::new(Vec::new()),
+            enter_hooks: RwLock::new(Vec::new()),
+            exit_hooks: RwLock::new(Vec::new()),
+            rejected: Event::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// Declare a transition from `from` to `to`, triggered by `event`.
+    pub fn add_transition(&self, from: S, event: E, to: S) {
+        self.transitions.write().unwrap().push(Transition { from, event, to, guard: None });
+    }
+
+    /// Declare a transition which is only performed if `guard` returns `true` for the current state and the event.
+    /// Transitions are checked in the order they were declared, so several guarded transitions can share the same state and event.
+    pub fn add_guarded_transition(&self, from: S, event: E, to: S, guard: impl Fn(&S, &E) -> bool + 'a) {
+        self.transitions.write().unwrap().push(Transition { from, event, to, guard: Some(Arc::new(guard)) });
+    }
+
+    /// Run `hook` every time the machine enters `state`. The hook receives the state that was left and the event that caused the transition.
+    pub fn on_enter(&self, state: S, hook: impl Fn(&S, &E) + 'a) {
+        self.enter_hooks.write().unwrap().push((state, Arc::new(hook)));
+    }
+
+    /// Run `hook` every time the machine leaves `state`. The hook receives the state that is about to be entered and the event that caused the transition.
+    pub fn on_exit(&self, state: S, hook: impl Fn(&S, &E) + 'a) {
+        self.exit_hooks.write().unwrap().push((state, Arc::new(hook)));
+    }
+
+    /// Get the current state.
+    pub fn state(&self) -> S {
+        self.state.get()
+    }
+
+    /// Listen to state changes. See [Notifier::listen].
+    pub fn listen(&self, listener: impl Fn(&S) + 'static) {
+        self.state.listen(listener);
+    }
+
+    /// The event every rejected transition is published through.
+    pub fn rejected(&self) -> &Event<'a, Rejection<S, E>> {
+        &self.rejected
+    }
+
+    /// Check whether `event` would cause a transition from the current state.
+    pub fn can_fire(&self, event: &E) -> bool {
+        let current = self.state.get();
+
+        self.candidates(&current, event).iter().any(|(_, guard)| allows(guard, &current, event))
+    }
+
+    /// Feed an event into the machine. Returns `true` if a transition was performed, otherwise notifies [rejected](StateMachine::rejected) and returns `false`.
+    pub fn fire(&self, event: E) -> bool {
+        let current = self.state.get();
+
+        let candidates = self.candidates(&current, &event);
+        let declared = !candidates.is_empty();
+        let target = candidates.into_iter().find(|(_, guard)| allows(guard, &current, &event)).map(|(to, _)| to);
+
+        let Some(target) = target else {
+            self.rejected.notify(Rejection {
+                state: current,
+                event,
+                reason: if declared { RejectionReason::Guarded } else { RejectionReason::Undeclared },
+            });
+
+            return false;
+        };
+
+        for hook in Self::hooks_for(&self.exit_hooks, &current) {
+            hook(&target, &event);
+        }
+
+        self.state.set(target.clone());
+
+        for hook in Self::hooks_for(&self.enter_hooks, &target) {
+            hook(&current, &event);
+        }
+
+        true
+    }
+
+    /// Copy the matching transitions out of the table, so guards run without holding its lock and may call back into the machine.
+    fn candidates(&self, current: &S, event: &E) -> Vec<(S, Option<GuardType<'a, S, E>>)> {
+        self.transitions.read().unwrap().iter()
+            .filter(|transition| transition.from == *current && transition.event == *event)
+            .map(|transition| (transition.to.clone(), transition.guard.clone()))
+            .collect()
+    }
+
+    fn hooks_for(hooks: &RwLock<Vec<(S, HookType<'a, S, E>)>>, state: &S) -> Vec<HookType<'a, S, E>> {
+        hooks.read().unwrap().iter().filter(|(s, _)| s == state).map(|(_, hook)| hook.clone()).collect()
+    }
+}
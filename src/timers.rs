@@ -0,0 +1,198 @@
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use crate::events::Event;
+
+/// A source of the current time for a [Scheduler].
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The real, monotonic clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock which only moves when told to, so timer logic can be tested deterministically.
+pub struct ManualClock {
+    now: RwLock<Instant>,
+}
+
+impl ManualClock {
+    /// Create a new ManualClock starting at the current time.
+    pub fn new() -> Self {
+        Self { now: RwLock::new(Instant::now()) }
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.write().unwrap() += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.read().unwrap()
+    }
+}
+
+/// A handle to a scheduled timer, which can be used to cancel it.
+#[derive(Clone)]
+pub struct TimerHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    /// Cancel the timer. Payloads which are already due but not delivered yet are dropped as well.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// An [Event] driven by a [Scheduler]. Dereferences to the event, so it can be subscribed to directly.
+pub struct Timer<'a> {
+    event: Rc<Event<'a, Instant>>,
+    handle: TimerHandle,
+}
+
+impl Timer<'_> {
+    pub fn handle(&self) -> TimerHandle {
+        self.handle.clone()
+    }
+
+    /// Cancel the timer. See [TimerHandle::cancel].
+    pub fn cancel(&self) {
+        self.handle.cancel();
+    }
+}
+
+impl<'a> Deref for Timer<'a> {
+    type Target = Event<'a, Instant>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+struct ScheduledTimer<'a> {
+    event: Rc<Event<'a, Instant>>,
+    handle: TimerHandle,
+    deadline: Instant,
+    period: Option<Duration>,
+}
+
+/// Keeps track of timers and fires them when [tick](Scheduler::tick) is called.
+pub struct Scheduler<'a, C: Clock = SystemClock> {
+    clock: C,
+    timers: RwLock<Vec<ScheduledTimer<'a>>>,
+}
+
+impl<'a, C: Clock> Scheduler<'a, C> {
+    /// Create a new Scheduler which reads the time from `clock`.
+    pub fn new(clock: C) -> Self {
+        Self { clock, timers: RwLock::new(Vec::new()) }
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Schedule a timer which fires every `period`, starting one period from now.
+    pub fn interval(&self, period: Duration) -> Timer<'a> {
+        assert!(!period.is_zero(), "An interval period must not be zero.");
+
+        self.schedule(period, Some(period))
+    }
+
+    /// Schedule a timer which fires once, `delay` from now.
+    pub fn after(&self, delay: Duration) -> Timer<'a> {
+        self.schedule(delay, None)
+    }
+
+    fn schedule(&self, delay: Duration, period: Option<Duration>) -> Timer<'a> {
+        let event = Rc::new(Event::new(RwLock::new(Vec::new())));
+        let handle = TimerHandle { cancelled: Arc::new(AtomicBool::new(false)) };
+
+        self.timers.write().unwrap().push(ScheduledTimer {
+            event: event.clone(),
+            handle: handle.clone(),
+            deadline: self.clock.now() + delay,
+            period,
+        });
+
+        Timer { event, handle }
+    }
+
+    /// The earliest instant at which a timer is due, if any timers are scheduled.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.read().unwrap().iter()
+            .filter(|timer| !timer.handle.is_cancelled())
+            .map(|timer| timer.deadline)
+            .min()
+    }
+
+    /// Fire every timer which is due according to the clock. An interval which missed several periods fires once per period,
+    /// and every timer is notified with the instant it was due at, in chronological order. Returns the number of notifications delivered.
+    pub fn tick(&self) -> usize {
+        let now = self.clock.now();
+        let mut due: Vec<(Instant, Rc<Event<'a, Instant>>, TimerHandle)> = Vec::new();
+
+        {
+            let mut timers = self.timers.write().unwrap();
+
+            timers.retain_mut(|timer| {
+                while !timer.handle.is_cancelled() && timer.deadline <= now {
+                    due.push((timer.deadline, timer.event.clone(), timer.handle.clone()));
+
+                    match timer.period {
+                        Some(period) => timer.deadline += period,
+                        None => return false,
+                    }
+                }
+
+                !timer.handle.is_cancelled()
+            });
+        }
+
+        due.sort_by_key(|(deadline, _, _)| *deadline);
+
+        let mut delivered = 0;
+
+        for (deadline, event, handle) in due {
+            if !handle.is_cancelled() {
+                event.notify(deadline);
+                delivered += 1;
+            }
+        }
+
+        delivered
+    }
+}
+
+impl<'a> Event<'a, Instant> {
+    /// Create an event which is notified every `period` by `scheduler`. See [Scheduler::interval].
+    pub fn interval<C: Clock>(scheduler: &Scheduler<'a, C>, period: Duration) -> Timer<'a> {
+        scheduler.interval(period)
+    }
+
+    /// Create an event which is notified once by `scheduler` after `delay`. See [Scheduler::after].
+    pub fn after<C: Clock>(scheduler: &Scheduler<'a, C>, delay: Duration) -> Timer<'a> {
+        scheduler.after(delay)
+    }
+}
@@ -0,0 +1,8 @@
+use eo::sjson::HasSJsonIdent;
+
+#[derive(HasSJsonIdent)]
+struct Icon {
+    texture: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use eo::sjson::ToSJson;
+
+#[derive(ToSJson)]
+struct Item {
+    #[sjson(renamed = "minecraft:item")]
+    item: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use eo::sjson;
+
+fn main() {
+    let items = [1, 2];
+    let _ = sjson! {
+        list [
+            for item in $items { $item }
+        ]
+    };
+}
@@ -0,0 +1,8 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson! {
+        damage = 7
+        icon = "icon_id"
+    };
+}
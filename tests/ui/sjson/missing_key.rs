@@ -0,0 +1,7 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson! {
+        = 7
+    };
+}
@@ -0,0 +1,7 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson!(schema = "../../schemas/malformed.json", {
+        minecraft:damage = 7
+    });
+}
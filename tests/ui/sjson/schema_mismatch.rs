@@ -0,0 +1,22 @@
+use eo::sjson;
+
+fn main() {
+    let damage = 3;
+
+    let _ = sjson!(schema = "../../schemas/item.json", {
+        format_version = "1.21.0",
+        minecraft:item {
+            description {
+                identifier = "eo:sword",
+                menu_category { category = "weapons" }
+            },
+            components {
+                minecraft:damage = $damage,
+                minecraft:icon = 12,
+                minecraft:hand_equiped = true,
+                minecraft:max_stack_size = 65,
+                minecraft:tags { tags ["eo:sword", false] }
+            }
+        }
+    });
+}
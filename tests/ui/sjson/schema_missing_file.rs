@@ -0,0 +1,7 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson!(schema = "../../schemas/missing.json", {
+        format_version = "1.21.0"
+    });
+}
@@ -0,0 +1,9 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson!(schema = "../../schemas/item.json", {
+        minecraft:item {
+            description {}
+        }
+    });
+}
@@ -0,0 +1,5 @@
+use eo::sjson_value;
+
+fn main() {
+    let _ = sjson_value!(+);
+}
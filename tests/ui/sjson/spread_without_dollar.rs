@@ -0,0 +1,9 @@
+use eo::sjson;
+
+fn main() {
+    let _ = sjson! {
+        object {
+            ..base
+        }
+    };
+}
@@ -0,0 +1,15 @@
+use eo::sjson;
+
+fn main() {
+    let tags = vec!["sword", "tool"];
+    let count = 3;
+
+    let _ = sjson! {
+        object {
+            ..$tags
+        },
+        list [
+            ..$count
+        ]
+    };
+}